@@ -0,0 +1,172 @@
+use reqwest::{Client, Proxy};
+use std::{sync::Mutex, time::Duration};
+
+use crate::{NotionClient, NotionClientError};
+
+pub(crate) const DEFAULT_BASE_URL: &str = "https://api.notion.com";
+pub(crate) const DEFAULT_NOTION_VERSION: &str = "2022-06-28";
+pub(crate) const DEFAULT_MAX_RETRIES: u32 = 5;
+pub(crate) const DEFAULT_RETRY_DELAY: Duration = Duration::from_millis(500);
+
+pub struct NotionClientBuilder {
+    token: String,
+    base_url: String,
+    version: String,
+    max_retries: u32,
+    retry_delay: Duration,
+    timeout: Option<Duration>,
+    connect_timeout: Option<Duration>,
+    proxy: Option<Proxy>,
+    user_agent: Option<String>,
+    client: Option<Client>,
+}
+
+impl NotionClientBuilder {
+    pub fn new(token: String) -> Self {
+        NotionClientBuilder {
+            token,
+            base_url: DEFAULT_BASE_URL.into(),
+            version: DEFAULT_NOTION_VERSION.into(),
+            max_retries: DEFAULT_MAX_RETRIES,
+            retry_delay: DEFAULT_RETRY_DELAY,
+            timeout: None,
+            connect_timeout: None,
+            proxy: None,
+            user_agent: None,
+            client: None,
+        }
+    }
+
+    /// Origin the `/v1/...` endpoints are resolved against, e.g. `http://127.0.0.1:8080`.
+    pub fn base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Value sent in the `Notion-Version` header.
+    pub fn version(mut self, version: impl Into<String>) -> Self {
+        self.version = version.into();
+        self
+    }
+
+    /// How often a rate limited request is retried before its error is returned.
+    pub fn max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Wait before the first retry of a rate limited request, doubled for every further
+    /// retry. A `Retry-After` header of the response takes precedence.
+    pub fn retry_delay(mut self, delay: Duration) -> Self {
+        self.retry_delay = delay;
+        self
+    }
+
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    pub fn proxy(mut self, proxy: Proxy) -> Self {
+        self.proxy = Some(proxy);
+        self
+    }
+
+    pub fn user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = Some(user_agent.into());
+        self
+    }
+
+    /// Use an already configured `reqwest::Client`.
+    ///
+    /// The timeout, proxy and user agent settings of this builder are ignored in that case.
+    pub fn client(mut self, client: Client) -> Self {
+        self.client = Some(client);
+        self
+    }
+
+    pub fn build(self) -> Result<NotionClient, NotionClientError> {
+        let client = match self.client {
+            Some(client) => client,
+            None => {
+                let mut builder = Client::builder();
+                if let Some(timeout) = self.timeout {
+                    builder = builder.timeout(timeout);
+                }
+                if let Some(timeout) = self.connect_timeout {
+                    builder = builder.connect_timeout(timeout);
+                }
+                if let Some(proxy) = self.proxy {
+                    builder = builder.proxy(proxy);
+                }
+                if let Some(user_agent) = self.user_agent {
+                    builder = builder.user_agent(user_agent);
+                }
+                builder.build()?
+            }
+        };
+
+        Ok(NotionClient {
+            client,
+            token: self.token,
+            base_url: self.base_url,
+            version: self.version,
+            max_retries: self.max_retries,
+            retry_delay: self.retry_delay,
+            synced_blocks: Mutex::default(),
+            link_titles: Mutex::default(),
+            users: Mutex::default(),
+            workspace_users: Mutex::default(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use pretty_assertions::assert_eq;
+
+    use super::{
+        DEFAULT_BASE_URL, DEFAULT_MAX_RETRIES, DEFAULT_NOTION_VERSION, DEFAULT_RETRY_DELAY,
+        NotionClientBuilder,
+    };
+    use crate::NotionClient;
+
+    #[test]
+    fn test_build_with_defaults() {
+        let client = NotionClientBuilder::new("token".into()).build().unwrap();
+
+        assert_eq!(client.base_url, DEFAULT_BASE_URL);
+        assert_eq!(client.version, DEFAULT_NOTION_VERSION);
+        assert_eq!(client.max_retries, DEFAULT_MAX_RETRIES);
+        assert_eq!(client.retry_delay, DEFAULT_RETRY_DELAY);
+
+        let client = NotionClient::new("token".into());
+        assert_eq!(client.base_url, DEFAULT_BASE_URL);
+        assert_eq!(client.version, DEFAULT_NOTION_VERSION);
+    }
+
+    #[test]
+    fn test_build_with_overrides() {
+        let client = NotionClientBuilder::new("token".into())
+            .base_url("http://127.0.0.1:8080/")
+            .version("2025-09-03")
+            .max_retries(2)
+            .retry_delay(Duration::from_secs(1))
+            .timeout(Duration::from_secs(10))
+            .user_agent("notion-to-markdown")
+            .build()
+            .unwrap();
+
+        assert_eq!(client.base_url, "http://127.0.0.1:8080");
+        assert_eq!(client.version, "2025-09-03");
+        assert_eq!(client.max_retries, 2);
+        assert_eq!(client.retry_delay, Duration::from_secs(1));
+    }
+}
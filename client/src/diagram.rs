@@ -0,0 +1,60 @@
+use std::{fs, io, path::Path};
+use tokio::process::Command;
+
+/// An external program that renders Mermaid source to SVG, such as the Mermaid CLI.
+///
+/// `{input}` and `{output}` in the arguments are replaced with the path of a file holding
+/// the diagram source and the path the SVG has to be written to.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DiagramCommand {
+    program: String,
+    args: Vec<String>,
+}
+
+impl DiagramCommand {
+    pub fn new(
+        program: impl Into<String>,
+        args: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        DiagramCommand {
+            program: program.into(),
+            args: args.into_iter().map(Into::into).collect(),
+        }
+    }
+
+    /// `mmdc -i {input} -o {output}`, from `@mermaid-js/mermaid-cli`.
+    pub fn mermaid_cli() -> Self {
+        DiagramCommand::new("mmdc", ["-i", "{input}", "-o", "{output}"])
+    }
+
+    pub(crate) async fn render(&self, source: &str, output: &Path) -> io::Result<()> {
+        let input = output.with_extension("mmd");
+        fs::write(&input, source)?;
+
+        let args = self.args.iter().map(|arg| {
+            arg.replace("{input}", &input.to_string_lossy())
+                .replace("{output}", &output.to_string_lossy())
+        });
+        let result = Command::new(&self.program).args(args).output().await;
+        fs::remove_file(&input)?;
+
+        let result = result?;
+        if !result.status.success() {
+            return Err(io::Error::other(format!(
+                "{} exited with {}: {}",
+                self.program,
+                result.status,
+                String::from_utf8_lossy(&result.stderr).trim()
+            )));
+        }
+        if !output.exists() {
+            return Err(io::Error::other(format!(
+                "{} did not write {}",
+                self.program,
+                output.display()
+            )));
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,257 @@
+use std::{error, fmt};
+
+#[derive(Debug)]
+pub enum NotionClientError {
+    Http(reqwest::Error),
+    Status(reqwest::StatusCode),
+    Api(NotionApiError),
+}
+
+impl fmt::Display for NotionClientError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NotionClientError::Http(e) => write!(f, "HTTP error: {}", e),
+            NotionClientError::Status(code) => write!(f, "Unexpected status code: {}", code),
+            NotionClientError::Api(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl error::Error for NotionClientError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            NotionClientError::Http(e) => Some(e),
+            NotionClientError::Status(_) => None,
+            NotionClientError::Api(e) => Some(e),
+        }
+    }
+}
+
+impl From<reqwest::Error> for NotionClientError {
+    fn from(err: reqwest::Error) -> Self {
+        NotionClientError::Http(err)
+    }
+}
+
+impl From<NotionApiError> for NotionClientError {
+    fn from(err: NotionApiError) -> Self {
+        NotionClientError::Api(err)
+    }
+}
+
+impl NotionClientError {
+    /// Builds the error for a non-success response, keeping the JSON error body when Notion sent one.
+    pub(crate) fn from_response(status: reqwest::StatusCode, body: &str) -> Self {
+        match serde_json::from_str::<ApiErrorBody>(body) {
+            Ok(body) => NotionClientError::Api(NotionApiError {
+                status,
+                code: body.code.as_str().into(),
+                message: body.message,
+                request_id: body.request_id,
+            }),
+            Err(_) => NotionClientError::Status(status),
+        }
+    }
+
+    pub fn is_rate_limited(&self) -> bool {
+        match self {
+            NotionClientError::Status(status) => *status == reqwest::StatusCode::TOO_MANY_REQUESTS,
+            NotionClientError::Api(e) => e.code == NotionErrorCode::RateLimited,
+            NotionClientError::Http(_) => false,
+        }
+    }
+}
+
+#[derive(serde::Deserialize)]
+struct ApiErrorBody {
+    code: String,
+    #[serde(default)]
+    message: String,
+    request_id: Option<String>,
+}
+
+/// Error returned by the Notion API, parsed from the response body.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NotionApiError {
+    pub status: reqwest::StatusCode,
+    pub code: NotionErrorCode,
+    pub message: String,
+    pub request_id: Option<String>,
+}
+
+impl NotionApiError {
+    /// Suggestion shown to users alongside the error message, if there is one for the code.
+    pub fn hint(&self) -> Option<&'static str> {
+        match self.code {
+            NotionErrorCode::ObjectNotFound => Some(
+                "Make sure the page exists and is shared with your integration (\"Connections\" in the page menu).",
+            ),
+            NotionErrorCode::Unauthorized => {
+                Some("Check that NOTION_TOKEN is a valid integration token.")
+            }
+            NotionErrorCode::RestrictedResource => {
+                Some("The integration lacks the capabilities required for this request.")
+            }
+            NotionErrorCode::RateLimited => Some("Notion is rate limiting requests; retry later."),
+            NotionErrorCode::MissingVersion => Some("Set a Notion-Version on the client."),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for NotionApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Notion API error {} ({}): {}",
+            self.code, self.status, self.message
+        )?;
+        if let Some(request_id) = &self.request_id {
+            write!(f, " [request_id: {}]", request_id)?;
+        }
+        Ok(())
+    }
+}
+
+impl error::Error for NotionApiError {}
+
+/// Error codes documented at <https://developers.notion.com/reference/status-codes>.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NotionErrorCode {
+    InvalidJson,
+    InvalidRequestUrl,
+    InvalidRequest,
+    InvalidGrant,
+    ValidationError,
+    MissingVersion,
+    Unauthorized,
+    RestrictedResource,
+    ObjectNotFound,
+    ConflictError,
+    RateLimited,
+    InternalServerError,
+    BadGateway,
+    ServiceUnavailable,
+    DatabaseConnectionUnavailable,
+    GatewayTimeout,
+    Other(String),
+}
+
+impl From<&str> for NotionErrorCode {
+    fn from(code: &str) -> Self {
+        match code {
+            "invalid_json" => NotionErrorCode::InvalidJson,
+            "invalid_request_url" => NotionErrorCode::InvalidRequestUrl,
+            "invalid_request" => NotionErrorCode::InvalidRequest,
+            "invalid_grant" => NotionErrorCode::InvalidGrant,
+            "validation_error" => NotionErrorCode::ValidationError,
+            "missing_version" => NotionErrorCode::MissingVersion,
+            "unauthorized" => NotionErrorCode::Unauthorized,
+            "restricted_resource" => NotionErrorCode::RestrictedResource,
+            "object_not_found" => NotionErrorCode::ObjectNotFound,
+            "conflict_error" => NotionErrorCode::ConflictError,
+            "rate_limited" => NotionErrorCode::RateLimited,
+            "internal_server_error" => NotionErrorCode::InternalServerError,
+            "bad_gateway" => NotionErrorCode::BadGateway,
+            "service_unavailable" => NotionErrorCode::ServiceUnavailable,
+            "database_connection_unavailable" => NotionErrorCode::DatabaseConnectionUnavailable,
+            "gateway_timeout" => NotionErrorCode::GatewayTimeout,
+            other => NotionErrorCode::Other(other.into()),
+        }
+    }
+}
+
+impl NotionErrorCode {
+    pub fn as_str(&self) -> &str {
+        match self {
+            NotionErrorCode::InvalidJson => "invalid_json",
+            NotionErrorCode::InvalidRequestUrl => "invalid_request_url",
+            NotionErrorCode::InvalidRequest => "invalid_request",
+            NotionErrorCode::InvalidGrant => "invalid_grant",
+            NotionErrorCode::ValidationError => "validation_error",
+            NotionErrorCode::MissingVersion => "missing_version",
+            NotionErrorCode::Unauthorized => "unauthorized",
+            NotionErrorCode::RestrictedResource => "restricted_resource",
+            NotionErrorCode::ObjectNotFound => "object_not_found",
+            NotionErrorCode::ConflictError => "conflict_error",
+            NotionErrorCode::RateLimited => "rate_limited",
+            NotionErrorCode::InternalServerError => "internal_server_error",
+            NotionErrorCode::BadGateway => "bad_gateway",
+            NotionErrorCode::ServiceUnavailable => "service_unavailable",
+            NotionErrorCode::DatabaseConnectionUnavailable => "database_connection_unavailable",
+            NotionErrorCode::GatewayTimeout => "gateway_timeout",
+            NotionErrorCode::Other(code) => code,
+        }
+    }
+}
+
+impl fmt::Display for NotionErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+    use reqwest::StatusCode;
+
+    use super::{NotionApiError, NotionClientError, NotionErrorCode};
+
+    #[test]
+    fn test_from_response_with_error_body() {
+        let body = r#"{
+            "object": "error",
+            "status": 404,
+            "code": "object_not_found",
+            "message": "Could not find block with ID: XXXXXXXX-XXXX-XXXX-XXXX-XXXXXXXXXXXX.",
+            "request_id": "b7b6ba0d-7a0b-4b45-8ac1-0a4a2f7b8f1a"
+        }"#;
+
+        let err = NotionClientError::from_response(StatusCode::NOT_FOUND, body);
+
+        match err {
+            NotionClientError::Api(e) => {
+                assert_eq!(
+                    e,
+                    NotionApiError {
+                        status: StatusCode::NOT_FOUND,
+                        code: NotionErrorCode::ObjectNotFound,
+                        message:
+                            "Could not find block with ID: XXXXXXXX-XXXX-XXXX-XXXX-XXXXXXXXXXXX."
+                                .into(),
+                        request_id: Some("b7b6ba0d-7a0b-4b45-8ac1-0a4a2f7b8f1a".into()),
+                    }
+                );
+                assert!(e.hint().is_some());
+            }
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn test_from_response_with_unknown_code() {
+        let body = r#"{"object":"error","status":400,"code":"something_new","message":"?"}"#;
+
+        let err = NotionClientError::from_response(StatusCode::BAD_REQUEST, body);
+
+        match err {
+            NotionClientError::Api(e) => {
+                assert_eq!(e.code, NotionErrorCode::Other("something_new".into()));
+                assert_eq!(e.request_id, None);
+            }
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn test_from_response_without_json_body() {
+        let err = NotionClientError::from_response(StatusCode::TOO_MANY_REQUESTS, "<html></html>");
+
+        assert!(matches!(
+            err,
+            NotionClientError::Status(StatusCode::TOO_MANY_REQUESTS)
+        ));
+        assert!(err.is_rate_limited());
+    }
+}
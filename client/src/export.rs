@@ -0,0 +1,751 @@
+use notion_to_markdown_core::{
+    Block, BlockChildren, ColumnLayout, DEFAULT_MAX_FILE_NAME_LENGTH, FileNamer,
+    MarkdownRenderOptions, NamingStrategy, PageRegistry, Snapshot, UserDirectory, code::Code,
+    linked_page_ids, mentioned_user_ids, normalize_page_id,
+};
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    error, fmt, fs,
+    io::{self, BufWriter, Write},
+    path::{Component, Path, PathBuf},
+    sync::Arc,
+};
+
+use crate::{
+    DiagramCommand, ExportProfile, ExportReport, ExportWarning, Manifest, ManifestPage,
+    NotionClient, NotionClientError, NotionErrorCode, Page, PageStatus, WarningKind,
+    profile::NavEntry,
+    state::{ExportState, PageState},
+};
+
+#[derive(Debug)]
+pub enum ExportError {
+    Client(NotionClientError),
+    Io(io::Error),
+    /// The export finished, but reported more warnings than [`Exporter::max_warnings`]
+    /// allows.
+    TooManyWarnings {
+        max: usize,
+        report: Box<ExportReport>,
+    },
+}
+
+impl fmt::Display for ExportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExportError::Client(e) => write!(f, "{}", e),
+            ExportError::Io(e) => write!(f, "IO error: {}", e),
+            ExportError::TooManyWarnings { max, report } => write!(
+                f,
+                "export reported {} warnings, more than the {} allowed",
+                report.warnings.len(),
+                max
+            ),
+        }
+    }
+}
+
+impl error::Error for ExportError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            ExportError::Client(e) => Some(e),
+            ExportError::Io(e) => Some(e),
+            ExportError::TooManyWarnings { .. } => None,
+        }
+    }
+}
+
+impl From<NotionClientError> for ExportError {
+    fn from(err: NotionClientError) -> Self {
+        ExportError::Client(err)
+    }
+}
+
+impl From<io::Error> for ExportError {
+    fn from(err: io::Error) -> Self {
+        ExportError::Io(err)
+    }
+}
+
+struct CrawledPage {
+    page: Page,
+    /// ID of the page this one is nested in, `None` for the exported page.
+    parent: Option<String>,
+    /// Place among the pages nested in the same parent, starting at 1.
+    position: usize,
+    path: String,
+    /// `None` when the page is unchanged since the previous export.
+    blocks: Option<(Vec<Block>, ExportReport)>,
+}
+
+/// Title and place of a page, kept for the navigation files after the pages are written.
+struct NavPage {
+    id: String,
+    parent: Option<String>,
+    title: String,
+    path: String,
+}
+
+/// Writes a page and every page nested in it as Markdown files into one directory.
+pub struct Exporter<'a> {
+    client: &'a NotionClient,
+    output_dir: PathBuf,
+    options: MarkdownRenderOptions,
+    profile: ExportProfile,
+    incremental: bool,
+    snapshots: bool,
+    diagram_command: Option<DiagramCommand>,
+    naming: NamingStrategy,
+    max_file_name_length: usize,
+    page_size: Option<u32>,
+    max_warnings: Option<usize>,
+    resolve_users: bool,
+}
+
+impl<'a> Exporter<'a> {
+    pub fn new(client: &'a NotionClient, output_dir: impl Into<PathBuf>) -> Self {
+        Exporter {
+            client,
+            output_dir: output_dir.into(),
+            options: ExportProfile::Plain.render_options(),
+            profile: ExportProfile::Plain,
+            incremental: true,
+            snapshots: false,
+            diagram_command: None,
+            naming: NamingStrategy::Title,
+            max_file_name_length: DEFAULT_MAX_FILE_NAME_LENGTH,
+            page_size: None,
+            max_warnings: None,
+            resolve_users: false,
+        }
+    }
+
+    pub fn options(mut self, options: MarkdownRenderOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Lay the export out for a static site generator. Replaces the render options with the
+    /// profile's, so call [`Exporter::options`] afterwards to change them further.
+    pub fn profile(mut self, profile: ExportProfile) -> Self {
+        self.profile = profile;
+        self.options = profile.render_options();
+        self
+    }
+
+    /// Skip pages whose `last_edited_time` matches the previous export, unless they moved or
+    /// link to a page that was added, moved or removed. Enabled by default.
+    ///
+    /// Nothing else is compared, so after changing the render options or the profile, or
+    /// editing the original of a synced block shown on other pages, export with this
+    /// disabled to rewrite every page.
+    pub fn incremental(mut self, incremental: bool) -> Self {
+        self.incremental = incremental;
+        self
+    }
+
+    /// Also write each page's block tree as `{name}.snapshot.json`, to re-render it offline.
+    pub fn snapshots(mut self, snapshots: bool) -> Self {
+        self.snapshots = snapshots;
+        self
+    }
+
+    /// Render Mermaid code blocks to SVG files next to their page with `command`, linking
+    /// the image instead of the code. Blocks the command fails on are kept as code.
+    pub fn diagram_command(mut self, command: DiagramCommand) -> Self {
+        self.diagram_command = Some(command);
+        self
+    }
+
+    /// How pages are named on disk. Pages whose names collide get a numbered suffix, in
+    /// the order they are found in.
+    pub fn naming(mut self, naming: NamingStrategy) -> Self {
+        self.naming = naming;
+        self
+    }
+
+    /// Longest file name of a page in bytes, without extension.
+    pub fn max_file_name_length(mut self, max_length: usize) -> Self {
+        self.max_file_name_length = max_length;
+        self
+    }
+
+    pub fn page_size(mut self, page_size: u32) -> Self {
+        self.page_size = Some(page_size);
+        self
+    }
+
+    /// Fail the export with [`ExportError::TooManyWarnings`] when it reports more than `max`
+    /// warnings. The pages and `manifest.json` are still written.
+    pub fn max_warnings(mut self, max: usize) -> Self {
+        self.max_warnings = Some(max);
+        self
+    }
+
+    /// List the users of the workspace before rendering, so mentions are written with their
+    /// current names and the email addresses [`MarkdownRenderOptions::user_mention_template`]
+    /// may use. Mentioned users that aren't listed, such as guests, are retrieved one by one.
+    /// They are added to the users of the render options.
+    pub fn resolve_users(mut self, resolve_users: bool) -> Self {
+        self.resolve_users = resolve_users;
+        self
+    }
+
+    pub async fn export(&self, page_id: &str) -> Result<ExportReport, ExportError> {
+        fs::create_dir_all(&self.output_dir)?;
+
+        let previous = if self.incremental {
+            ExportState::load(&self.output_dir)
+        } else {
+            ExportState::default()
+        };
+        let mut state = ExportState::default();
+        let mut report = ExportReport::default();
+
+        // Every page is crawled before any is rendered, so links can resolve to pages found
+        // later in the tree.
+        let mut pages = self.crawl(page_id, &previous, &mut report).await?;
+        let mut users = if self.resolve_users {
+            Some(self.list_users(&mut report).await?)
+        } else {
+            None
+        };
+        let crawl_warnings = report.warnings.clone();
+        self.assign_paths(&mut pages);
+        let nav_pages: Vec<NavPage> = pages
+            .iter()
+            .map(|page| NavPage {
+                id: page.page.id.clone(),
+                parent: page.parent.clone(),
+                title: page.page.title.clone(),
+                path: page.path.clone(),
+            })
+            .collect();
+
+        let mut registry = PageRegistry::new();
+        for page in &pages {
+            registry.insert(&page.page.id, page.path.clone(), page.page.title.clone());
+        }
+
+        // A page that didn't change is written again when it moved, or when it links to a page
+        // that was added, moved or removed. States from before links were recorded don't say
+        // what a page links to, so then every page is.
+        let current: HashSet<&str> = pages.iter().map(|page| page.page.id.as_str()).collect();
+        let moved: HashSet<String> = pages
+            .iter()
+            .filter(|page| previous.pages.get(&page.page.id).map(|p| &p.path) != Some(&page.path))
+            .map(|page| page.page.id.as_str())
+            .chain(
+                previous
+                    .pages
+                    .keys()
+                    .map(String::as_str)
+                    .filter(|id| !current.contains(id)),
+            )
+            .map(normalize_page_id)
+            .collect();
+        for page in pages.iter_mut().filter(|page| page.blocks.is_none()) {
+            let links = previous.pages[&page.page.id].links.as_ref();
+            let stale = !moved.is_empty()
+                && (moved.contains(&normalize_page_id(&page.page.id))
+                    || links.is_none_or(|links| links.iter().any(|link| moved.contains(link))));
+            if stale {
+                page.blocks = Some(
+                    self.client
+                        .retrieve_block_children_with_report(&page.page.id, None, self.page_size)
+                        .await?,
+                );
+            }
+        }
+
+        if let Some(users) = users.as_mut() {
+            self.retrieve_mentioned_users(&pages, users).await?;
+        }
+        let users = users.map(Arc::new).or_else(|| self.options.users.clone());
+
+        let registry = Arc::new(registry);
+        let mut diagram_command = self.diagram_command.as_ref();
+        let mut manifest_pages = Vec::with_capacity(pages.len());
+        for page in pages {
+            let Some((mut blocks, mut page_report)) = page.blocks else {
+                let page_state = previous.pages[&page.page.id].clone();
+                report.unchanged.push(PathBuf::from(&page.path));
+                report.warnings.extend(page_state.warnings.iter().cloned());
+                manifest_pages.push(manifest_page(
+                    &page.page,
+                    &page_state,
+                    PageStatus::Unchanged,
+                ));
+                state.pages.insert(page.page.id.clone(), page_state);
+                continue;
+            };
+
+            let assets = self
+                .render_diagrams(
+                    &mut diagram_command,
+                    &mut blocks,
+                    &page.path,
+                    &mut page_report,
+                )
+                .await?;
+            if self.options.column_layout == ColumnLayout::Table {
+                for block_id in sequential_column_lists(&blocks) {
+                    page_report.warn(ExportWarning {
+                        kind: WarningKind::Layout,
+                        block_id,
+                        block_type: Some("column_list".into()),
+                        message: "columns hold more than paragraphs, so they are written one \
+                            after another instead of as a table"
+                            .into(),
+                    });
+                }
+            }
+
+            let options = MarkdownRenderOptions {
+                page_registry: Some(registry.clone()),
+                current_page_path: Some(page.path.clone()),
+                users: users.clone(),
+                ..self.options.clone()
+            };
+            let front_matter = self.profile.front_matter(
+                &page.page.title,
+                &page.page.last_edited_time,
+                page.position,
+            );
+            write_page(
+                &self.output_dir.join(&page.path),
+                &front_matter,
+                &blocks,
+                &options,
+            )?;
+            if self.snapshots {
+                let snapshot = Snapshot::new(page.page.clone().into(), blocks.clone());
+                write_file(
+                    &self.output_dir.join(snapshot_path(&page.path)),
+                    serde_json::to_string_pretty(&snapshot).map_err(io::Error::from)?,
+                )?;
+            }
+
+            report.written.push(PathBuf::from(&page.path));
+            let page_state = PageState {
+                title: page.page.title.clone(),
+                path: page.path,
+                last_edited_time: page.page.last_edited_time.clone(),
+                child_pages: child_page_ids(&blocks),
+                links: Some(linked_page_ids(&blocks)),
+                assets,
+                warnings: page_report.warnings.clone(),
+            };
+            manifest_pages.push(manifest_page(&page.page, &page_state, PageStatus::Written));
+            state.pages.insert(page.page.id, page_state);
+            report.merge(page_report);
+        }
+
+        self.write_nav_files(&nav_pages)?;
+        self.remove_stale_files(&previous, &state, &mut report)?;
+        state.save(&self.output_dir)?;
+        Manifest {
+            root_page_id: page_id.into(),
+            pages: manifest_pages,
+            removed: report
+                .removed
+                .iter()
+                .map(|path| path.to_string_lossy().into_owned())
+                .collect(),
+            warnings: crawl_warnings,
+        }
+        .save(&self.output_dir)?;
+
+        match self.max_warnings {
+            Some(max) if report.warnings.len() > max => Err(ExportError::TooManyWarnings {
+                max,
+                report: Box::new(report),
+            }),
+            _ => Ok(report),
+        }
+    }
+
+    /// The users of the render options and of the workspace. Without access to user
+    /// information, mentions keep the names Notion put in them.
+    async fn list_users(&self, report: &mut ExportReport) -> Result<UserDirectory, ExportError> {
+        let mut users = self.options.users.as_deref().cloned().unwrap_or_default();
+
+        match self.client.list_users().await {
+            Ok(listed) => {
+                for user in listed {
+                    users.insert(user);
+                }
+            }
+            // The integration lacks the capability to read user information.
+            Err(NotionClientError::Api(e))
+                if matches!(e.code, NotionErrorCode::RestrictedResource) =>
+            {
+                report.warn(ExportWarning {
+                    kind: WarningKind::ApiError,
+                    block_id: None,
+                    block_type: None,
+                    message: format!("users could not be listed for mentions: {}", e),
+                });
+            }
+            Err(e) => return Err(e.into()),
+        }
+
+        Ok(users)
+    }
+
+    /// Retrieves the users mentioned in the pages to write that listing didn't return, such
+    /// as guests. Users that were removed or can't be read stay unknown.
+    async fn retrieve_mentioned_users(
+        &self,
+        pages: &[CrawledPage],
+        users: &mut UserDirectory,
+    ) -> Result<(), ExportError> {
+        let blocks = pages.iter().filter_map(|page| page.blocks.as_ref());
+        for (blocks, _) in blocks {
+            for user_id in mentioned_user_ids(blocks) {
+                if users.get(&user_id).is_some() {
+                    continue;
+                }
+                match self.client.retrieve_user(&user_id).await {
+                    Ok(user) => users.insert(user),
+                    Err(NotionClientError::Api(e))
+                        if matches!(e.code, NotionErrorCode::ObjectNotFound) => {}
+                    // Without the capability to read users, none of the others can be read.
+                    Err(NotionClientError::Api(e))
+                        if matches!(e.code, NotionErrorCode::RestrictedResource) =>
+                    {
+                        return Ok(());
+                    }
+                    Err(e) => return Err(e.into()),
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Walks the page tree from `page_id`, retrieving the blocks of every page that changed
+    /// since the previous export.
+    async fn crawl(
+        &self,
+        page_id: &str,
+        previous: &ExportState,
+        report: &mut ExportReport,
+    ) -> Result<Vec<CrawledPage>, ExportError> {
+        let mut pages = Vec::new();
+        let mut queue = VecDeque::from([(page_id.to_string(), None)]);
+        let mut visited = HashSet::new();
+
+        while let Some((id, parent)) = queue.pop_front() {
+            if !visited.insert(id.clone()) {
+                continue;
+            }
+
+            let page = match self.client.retrieve_page(&id).await {
+                Ok(page) => page,
+                Err(NotionClientError::Api(e))
+                    if id != page_id
+                        && matches!(
+                            e.code,
+                            NotionErrorCode::ObjectNotFound | NotionErrorCode::RestrictedResource
+                        ) =>
+                {
+                    report.warn(ExportWarning {
+                        kind: WarningKind::ApiError,
+                        block_id: Some(id),
+                        block_type: Some("child_page".into()),
+                        message: e.to_string(),
+                    });
+                    continue;
+                }
+                Err(e) => return Err(e.into()),
+            };
+            if page.archived {
+                continue;
+            }
+
+            // Paths are only known once the whole tree is; a page whose path changes is
+            // fetched again before rendering.
+            if let Some(previous) = previous.pages.get(&id)
+                && previous.last_edited_time == page.last_edited_time
+                && self.output_dir.join(&previous.path).exists()
+            {
+                queue.extend(
+                    previous
+                        .child_pages
+                        .iter()
+                        .map(|child| (child.clone(), Some(id.clone()))),
+                );
+                pages.push(CrawledPage {
+                    page,
+                    parent,
+                    position: 0,
+                    path: String::new(),
+                    blocks: None,
+                });
+                continue;
+            }
+
+            let (blocks, page_report) = self
+                .client
+                .retrieve_block_children_with_report(&id, None, self.page_size)
+                .await?;
+            queue.extend(
+                child_page_ids(&blocks)
+                    .into_iter()
+                    .map(|child| (child, Some(id.clone()))),
+            );
+            pages.push(CrawledPage {
+                page,
+                parent,
+                position: 0,
+                path: String::new(),
+                blocks: Some((blocks, page_report)),
+            });
+        }
+
+        Ok(pages)
+    }
+
+    /// Places every page according to the profile. Pages come in crawl order, so a parent
+    /// always has its path before its children.
+    fn assign_paths(&self, pages: &mut [CrawledPage]) {
+        let parents: HashSet<String> = pages.iter().filter_map(|p| p.parent.clone()).collect();
+        let mut paths: HashMap<String, String> = HashMap::new();
+        let mut positions: HashMap<Option<String>, usize> = HashMap::new();
+        let mut namer = FileNamer::new(self.naming).max_length(self.max_file_name_length);
+
+        for page in pages {
+            let parent_path = page
+                .parent
+                .as_ref()
+                .and_then(|parent| paths.get(parent))
+                .map(String::as_str);
+
+            let dir = self.profile.name_dir(parent_path);
+            for reserved in self.profile.reserved_names() {
+                namer.reserve(dir, reserved);
+            }
+            let name = namer.name(dir, &page.page.title, &page.page.id);
+            page.path = self
+                .profile
+                .page_path(parent_path, &name, parents.contains(&page.page.id));
+
+            let position = positions.entry(page.parent.clone()).or_default();
+            *position += 1;
+            page.position = *position;
+
+            paths.insert(page.page.id.clone(), page.path.clone());
+        }
+    }
+
+    /// Writes the navigation files of the profile, such as `mkdocs.yml`.
+    fn write_nav_files(&self, pages: &[NavPage]) -> io::Result<()> {
+        fn entry<'p>(pages: &'p [NavPage], page: &'p NavPage) -> NavEntry<'p> {
+            NavEntry {
+                title: &page.title,
+                path: &page.path,
+                children: pages
+                    .iter()
+                    .filter(|child| child.parent.as_ref() == Some(&page.id))
+                    .map(|child| entry(pages, child))
+                    .collect(),
+            }
+        }
+
+        let Some(root) = pages.first() else {
+            return Ok(());
+        };
+        let files = self.profile.nav_files(&entry(pages, root), |path| {
+            fs::read_to_string(self.output_dir.join(path)).ok()
+        });
+        for (path, content) in files {
+            write_file(&self.output_dir.join(path), content)?;
+        }
+
+        Ok(())
+    }
+
+    /// Renders the Mermaid diagrams in `blocks` to SVG files and returns their paths.
+    ///
+    /// A command that cannot be started is dropped for the rest of the export; either way the
+    /// affected blocks stay code blocks and a warning is reported.
+    async fn render_diagrams(
+        &self,
+        command: &mut Option<&DiagramCommand>,
+        blocks: &mut [Block],
+        page_path: &str,
+        report: &mut ExportReport,
+    ) -> io::Result<Vec<String>> {
+        let mut assets = Vec::new();
+        let mut diagrams = Vec::new();
+        mermaid_blocks(blocks, &mut diagrams);
+
+        for (index, (block_id, code)) in diagrams.into_iter().enumerate() {
+            let Some(diagram_command) = command else {
+                break;
+            };
+
+            let path = self
+                .profile
+                .asset_path(page_path, &format!("diagram-{}.svg", index + 1));
+            let output = self.output_dir.join(&path);
+            if let Some(dir) = output.parent() {
+                fs::create_dir_all(dir)?;
+            }
+            match diagram_command.render(&code.source(), &output).await {
+                Ok(()) => {
+                    code.set_diagram_path(path.clone());
+                    assets.push(path);
+                }
+                Err(e) => {
+                    if e.kind() == io::ErrorKind::NotFound {
+                        *command = None;
+                    }
+                    report.warn(ExportWarning {
+                        kind: WarningKind::Diagram,
+                        block_id,
+                        block_type: Some("code".into()),
+                        message: format!("could not render diagram: {}", e),
+                    });
+                }
+            }
+        }
+
+        Ok(assets)
+    }
+
+    /// Deletes files of pages that were exported last time but are gone from the tree now,
+    /// and assets no page refers to anymore.
+    fn remove_stale_files(
+        &self,
+        previous: &ExportState,
+        state: &ExportState,
+        report: &mut ExportReport,
+    ) -> io::Result<()> {
+        let current: HashSet<&str> = state.pages.values().map(|p| p.path.as_str()).collect();
+        let current_assets: HashSet<&str> = state
+            .pages
+            .values()
+            .flat_map(|p| p.assets.iter().map(String::as_str))
+            .collect();
+
+        for page in previous.pages.values() {
+            for asset in &page.assets {
+                let path = self.output_dir.join(asset);
+                if !current_assets.contains(asset.as_str())
+                    && path.exists()
+                    && is_inside(&self.output_dir, &path)
+                {
+                    fs::remove_file(&path)?;
+                }
+            }
+
+            if current.contains(page.path.as_str()) {
+                continue;
+            }
+
+            let path = self.output_dir.join(&page.path);
+            if path.exists() && is_inside(&self.output_dir, &path) {
+                fs::remove_file(&path)?;
+                report.removed.push(PathBuf::from(&page.path));
+            }
+
+            let snapshot = self.output_dir.join(snapshot_path(&page.path));
+            if snapshot.exists() && is_inside(&self.output_dir, &snapshot) {
+                fs::remove_file(&snapshot)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn manifest_page(page: &Page, state: &PageState, status: PageStatus) -> ManifestPage {
+    ManifestPage {
+        id: page.id.clone(),
+        title: state.title.clone(),
+        path: state.path.clone(),
+        last_edited_time: state.last_edited_time.clone(),
+        status,
+        assets: state.assets.clone(),
+        warnings: state.warnings.clone(),
+    }
+}
+
+/// IDs of the `child_page` blocks anywhere in `blocks`, in document order.
+fn child_page_ids(blocks: &[Block]) -> Vec<String> {
+    let mut ids = Vec::new();
+    for block in blocks {
+        if let Block::ChildPage(page) = block {
+            ids.push(page.id().to_string());
+        }
+        ids.extend(child_page_ids(block.children()));
+    }
+    ids
+}
+
+/// IDs of the column lists anywhere in `blocks` that [`ColumnLayout::Table`] lays out
+/// sequentially, in document order.
+fn sequential_column_lists(blocks: &[Block]) -> Vec<Option<String>> {
+    let mut ids = Vec::new();
+    for block in blocks {
+        if let Block::ColumnList(columns) = block
+            && !columns.fits_table()
+        {
+            ids.push(block.id().map(String::from));
+        }
+        ids.extend(sequential_column_lists(block.children()));
+    }
+    ids
+}
+
+/// The Mermaid code blocks anywhere in `blocks`, with their block IDs, in document order.
+fn mermaid_blocks<'b>(blocks: &'b mut [Block], found: &mut Vec<(Option<String>, &'b mut Code)>) {
+    for block in blocks {
+        let id = block.id().map(String::from);
+        if let Block::Code(code) = block {
+            if code.is_mermaid() {
+                found.push((id, code));
+            }
+        } else {
+            mermaid_blocks(block.children_mut(), found);
+        }
+    }
+}
+
+/// `Page.md` → `Page.snapshot.json`
+fn snapshot_path(path: &str) -> String {
+    format!("{}.snapshot.json", path.strip_suffix(".md").unwrap_or(path))
+}
+
+fn write_file(path: &Path, content: impl AsRef<[u8]>) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(path, content)
+}
+
+/// Streams a page into `path`, so large pages are never held in memory as a whole.
+fn write_page(
+    path: &Path,
+    front_matter: &str,
+    blocks: &Vec<Block>,
+    options: &MarkdownRenderOptions,
+) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let mut out = BufWriter::new(fs::File::create(path)?);
+    out.write_all(front_matter.as_bytes())?;
+    blocks.write_markdown_to(&mut out, 0, options)?;
+    out.flush()
+}
+
+/// Whether `path` lies in `dir`, guarding deletions against paths from a tampered state file.
+fn is_inside(dir: &Path, path: &Path) -> bool {
+    path.strip_prefix(dir).is_ok_and(|relative| {
+        relative
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)))
+    })
+}
@@ -1,64 +1,198 @@
-use notion_to_markdown_core::Block;
-use reqwest::Client;
-use std::{error, fmt, time::Duration};
+pub use builder::NotionClientBuilder;
+pub use diagram::DiagramCommand;
+pub use error::{NotionApiError, NotionClientError, NotionErrorCode};
+pub use export::{ExportError, Exporter};
+pub use manifest::{Manifest, ManifestPage, PageStatus};
+use notion_to_markdown_core::{
+    Block, Snapshot, User,
+    link_to_page::{LinkTargetTitle, LinkToPage},
+    normalize_page_id,
+    unparsed::Unparsed,
+};
+pub use page::Page;
+use page::{ApiDatabase, ApiPage};
+pub use profile::{ExportProfile, ParseProfileError};
+pub use report::{ExportReport, ExportWarning, WarningKind};
+use reqwest::{Client, Method};
+use serde::{Deserialize, de::DeserializeOwned};
+use std::{collections::HashMap, sync::Mutex, time::Duration};
 use tokio::time::sleep;
+use user::{ApiUser, ApiUserList};
 
-#[derive(Debug)]
-pub enum NotionClientError {
-    Http(reqwest::Error),
-    Status(reqwest::StatusCode),
-}
-
-impl fmt::Display for NotionClientError {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            NotionClientError::Http(e) => write!(f, "HTTP error: {}", e),
-            NotionClientError::Status(code) => write!(f, "Unexpected status code: {}", code),
-        }
-    }
-}
-
-impl error::Error for NotionClientError {
-    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
-        match self {
-            NotionClientError::Http(e) => Some(e),
-            NotionClientError::Status(_) => None,
-        }
-    }
-}
-
-impl From<reqwest::Error> for NotionClientError {
-    fn from(err: reqwest::Error) -> Self {
-        NotionClientError::Http(err)
-    }
-}
+mod builder;
+mod diagram;
+mod error;
+mod export;
+mod manifest;
+mod page;
+mod profile;
+mod report;
+mod state;
+mod user;
 
 pub struct NotionClient {
     client: Client,
     token: String,
+    base_url: String,
     version: String,
+    /// Retries of a rate limited request before giving up.
+    max_retries: u32,
+    /// Wait before the first retry, doubled for every further one.
+    retry_delay: Duration,
+    /// Hydrated children of original synced blocks, shared by all of their copies.
+    synced_blocks: Mutex<HashMap<String, Vec<Block>>>,
+    /// Titles of `link_to_page` targets, by page or database ID.
+    link_titles: Mutex<HashMap<String, LinkTargetTitle>>,
+    /// Users listed or retrieved so far, by ID without dashes.
+    users: Mutex<HashMap<String, User>>,
+    /// Every user of the workspace, once listed.
+    workspace_users: Mutex<Option<Vec<User>>>,
+}
+
+/// Longest wait between retries of a rate limited request that has no `Retry-After`.
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(30);
+
+/// Most blocks Notion accepts in a single "append block children" request.
+const APPEND_BLOCK_LIMIT: usize = 100;
+
+#[derive(serde::Deserialize)]
+struct ApiAppendBlockChildrenResponse {
+    results: Vec<ApiBlockHeader>,
 }
 
 #[derive(serde::Deserialize)]
 struct ApiBlockChildrenResponse {
-    results: Vec<ApiBlock>,
+    results: Vec<serde_json::Value>,
     next_cursor: Option<String>,
 }
 
-#[derive(serde::Deserialize)]
 struct ApiBlock {
     id: String,
     has_children: bool,
-    #[serde(flatten)]
     block: Block,
 }
 
+#[derive(serde::Deserialize)]
+struct ApiBlockHeader {
+    id: String,
+    has_children: bool,
+}
+
+impl ApiBlock {
+    /// Deserializes a single block, falling back to `Block::Unparsed` so one bad block
+    /// doesn't fail the whole page.
+    fn from_value(value: serde_json::Value, report: &mut ExportReport) -> ApiBlock {
+        // Parsed separately so blocks that read `id` themselves still see it.
+        let parsed = ApiBlockHeader::deserialize(&value)
+            .and_then(|header| Ok((header, Block::deserialize(&value)?)));
+
+        match parsed {
+            Ok((header, block)) => {
+                if matches!(block, Block::Unsupported | Block::Unexpected) {
+                    report.warn(ExportWarning {
+                        kind: WarningKind::UnsupportedBlock,
+                        block_id: Some(header.id.clone()),
+                        block_type: value.get("type").and_then(|v| v.as_str()).map(String::from),
+                        message: "the block type cannot be exported".into(),
+                    });
+                }
+
+                ApiBlock {
+                    id: header.id,
+                    has_children: header.has_children,
+                    block,
+                }
+            }
+            Err(e) => {
+                let id = value.get("id").and_then(|v| v.as_str()).map(String::from);
+                let block_type = value.get("type").and_then(|v| v.as_str()).map(String::from);
+
+                report.warn(ExportWarning {
+                    kind: WarningKind::UnparsedBlock,
+                    block_id: id.clone(),
+                    block_type: block_type.clone(),
+                    message: e.to_string(),
+                });
+
+                // Its children may parse, and would be lost with it otherwise.
+                let has_children = value.get("has_children").and_then(|v| v.as_bool());
+                ApiBlock {
+                    id: id.clone().unwrap_or_default(),
+                    has_children: has_children.unwrap_or(false),
+                    block: Block::Unparsed(Unparsed::new(id, block_type, value.to_string())),
+                }
+            }
+        }
+    }
+}
+
 impl NotionClient {
+    /// A client with the default settings of [`NotionClientBuilder`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the TLS backend cannot be initialized, like `reqwest::Client::new`.
     pub fn new(token: String) -> Self {
-        NotionClient {
-            client: Client::new(),
-            token,
-            version: "2022-06-28".into(),
+        NotionClientBuilder::new(token)
+            .build()
+            .expect("the default HTTP client cannot be built")
+    }
+
+    pub fn builder(token: String) -> NotionClientBuilder {
+        NotionClientBuilder::new(token)
+    }
+
+    /// Sends a GET request to the API, waiting and retrying while rate limited.
+    async fn get<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        query: &[(&str, String)],
+    ) -> Result<T, NotionClientError> {
+        self.send(Method::GET, path, query, None).await
+    }
+
+    /// Sends a request to the API, waiting and retrying while rate limited, up to
+    /// `max_retries` times.
+    ///
+    /// The wait is the `Retry-After` of the response, or else an exponential backoff.
+    async fn send<T: DeserializeOwned>(
+        &self,
+        method: Method,
+        path: &str,
+        query: &[(&str, String)],
+        body: Option<&serde_json::Value>,
+    ) -> Result<T, NotionClientError> {
+        let mut retries = 0;
+        loop {
+            let mut req = self
+                .client
+                .request(method.clone(), format!("{}{}", self.base_url, path))
+                .bearer_auth(&self.token)
+                .header("Notion-Version", &self.version)
+                .query(query);
+            if let Some(body) = body {
+                req = req.json(body);
+            }
+            let resp = req.send().await?;
+
+            let status = resp.status();
+            if status.is_success() {
+                return Ok(resp.json::<T>().await?);
+            }
+
+            let retry_after = retry_after(resp.headers());
+            let body = resp.text().await?;
+            match NotionClientError::from_response(status, &body) {
+                e if e.is_rate_limited() && retries < self.max_retries => {
+                    let backoff = self
+                        .retry_delay
+                        .saturating_mul(2u32.saturating_pow(retries))
+                        .min(MAX_RETRY_DELAY);
+                    sleep(retry_after.unwrap_or(backoff)).await;
+                    retries += 1;
+                }
+                e => return Err(e),
+            }
         }
     }
 
@@ -71,29 +205,16 @@ impl NotionClient {
         #[cfg(feature = "log")]
         log::info!("RETRIEVING BLOCK: {}", block_id);
 
-        let mut req = self
-            .client
-            .get(&format!(
-                "https://api.notion.com/v1/blocks/{}/children",
-                block_id
-            ))
-            .bearer_auth(&self.token)
-            .header("Notion-Version", &self.version);
-
+        let mut query = Vec::new();
         if let Some(cursor) = start_cursor {
-            req = req.query(&[("start_cursor", cursor)]);
+            query.push(("start_cursor", cursor.to_string()));
         }
         if let Some(size) = page_size {
-            req = req.query(&[("page_size", size.to_string())]);
+            query.push(("page_size", size.to_string()));
         }
 
-        let resp = req.send().await?;
-        let status = resp.status();
-        if !status.is_success() {
-            return Err(NotionClientError::Status(status));
-        }
-        let body = resp.json::<ApiBlockChildrenResponse>().await?;
-        Ok(body)
+        self.get(&format!("/v1/blocks/{}/children", block_id), &query)
+            .await
     }
 
     async fn retrieve_block_children_nodes(
@@ -101,28 +222,21 @@ impl NotionClient {
         block_id: &str,
         initial_cursor: Option<&str>,
         page_size: Option<u32>,
+        report: &mut ExportReport,
     ) -> Result<Vec<ApiBlock>, NotionClientError> {
         let mut all_results = Vec::new();
         let mut cursor = initial_cursor.map(|s| s.to_string());
 
         loop {
-            let resp = loop {
-                match self
-                    ._retrieve_block_children(block_id, cursor.as_deref(), page_size)
-                    .await
-                {
-                    Ok(res) => break res,
-                    Err(NotionClientError::Status(code))
-                        if code == reqwest::StatusCode::TOO_MANY_REQUESTS =>
-                    {
-                        sleep(Duration::from_millis(500)).await;
-                        continue;
-                    }
-                    Err(e) => return Err(e),
-                }
-            };
+            let resp = self
+                ._retrieve_block_children(block_id, cursor.as_deref(), page_size)
+                .await?;
 
-            all_results.extend(resp.results);
+            all_results.extend(
+                resp.results
+                    .into_iter()
+                    .map(|value| ApiBlock::from_value(value, report)),
+            );
 
             if let Some(next) = resp.next_cursor {
                 cursor = Some(next);
@@ -134,21 +248,59 @@ impl NotionClient {
         Ok(all_results)
     }
 
+    pub async fn retrieve_page(&self, page_id: &str) -> Result<Page, NotionClientError> {
+        #[cfg(feature = "log")]
+        log::info!("RETRIEVING PAGE: {}", page_id);
+
+        let page: ApiPage = self.get(&format!("/v1/pages/{}", page_id), &[]).await?;
+        Ok(page.into())
+    }
+
     fn hydrate_block<'a>(
         &'a self,
         mut block: ApiBlock,
         page_size: Option<u32>,
+        report: &'a mut ExportReport,
     ) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<Block, NotionClientError>> + 'a>>
     {
         Box::pin(async move {
-            if block.has_children {
-                let children = self
-                    .retrieve_block_children_nodes(&block.id, None, page_size)
+            if let Block::LinkToPage(item) = &mut block.block {
+                let title = self
+                    .retrieve_link_target_title(&block.id, item, report)
+                    .await?;
+                item.set_target_title(title);
+            }
+
+            if let Block::SyncedBlock(item) = &block.block
+                && let Some(original_id) = item.synced_from()
+            {
+                let original_id = original_id.to_string();
+                for child in self
+                    .retrieve_synced_children(&block.id, &original_id, page_size, report)
+                    .await?
+                {
+                    block.block.append(child);
+                }
+            } else if block.has_children
+                // Their children are the content of another page, exported on its own.
+                && !matches!(block.block, Block::ChildPage(_) | Block::ChildDatabase(_))
+            {
+                let nodes = self
+                    .retrieve_block_children_nodes(&block.id, None, page_size, report)
                     .await?;
+                let mut children = Vec::with_capacity(nodes.len());
+                for node in nodes {
+                    children.push(self.hydrate_block(node, page_size, report).await?);
+                }
+
+                if let Block::SyncedBlock(_) = &block.block {
+                    self.synced_blocks
+                        .lock()
+                        .unwrap()
+                        .insert(block.id.clone(), children.clone());
+                }
                 for child in children {
-                    block
-                        .block
-                        .append(self.hydrate_block(child, page_size).await?);
+                    block.block.append(child);
                 }
             }
 
@@ -156,21 +308,361 @@ impl NotionClient {
         })
     }
 
+    async fn retrieve_link_target_title(
+        &self,
+        block_id: &str,
+        item: &LinkToPage,
+        report: &mut ExportReport,
+    ) -> Result<LinkTargetTitle, NotionClientError> {
+        let target_id = item.target_id();
+        if let Some(title) = self.link_titles.lock().unwrap().get(target_id) {
+            return Ok(title.clone());
+        }
+
+        let title = if item.is_database() {
+            self.get::<ApiDatabase>(&format!("/v1/databases/{}", target_id), &[])
+                .await
+                .map(|database| database.title())
+        } else {
+            self.retrieve_page(target_id).await.map(|page| page.title)
+        };
+        let title = match title {
+            Ok(title) => LinkTargetTitle::Title(title),
+            Err(NotionClientError::Api(e))
+                if matches!(
+                    e.code,
+                    NotionErrorCode::ObjectNotFound | NotionErrorCode::RestrictedResource
+                ) =>
+            {
+                report.warn(ExportWarning {
+                    kind: WarningKind::ApiError,
+                    block_id: Some(block_id.into()),
+                    block_type: Some("link_to_page".into()),
+                    message: format!("linked page {} is not accessible: {}", target_id, e),
+                });
+                LinkTargetTitle::Inaccessible
+            }
+            Err(e) => return Err(e),
+        };
+
+        self.link_titles
+            .lock()
+            .unwrap()
+            .insert(target_id.into(), title.clone());
+
+        Ok(title)
+    }
+
+    async fn retrieve_synced_children(
+        &self,
+        block_id: &str,
+        original_id: &str,
+        page_size: Option<u32>,
+        report: &mut ExportReport,
+    ) -> Result<Vec<Block>, NotionClientError> {
+        if let Some(children) = self.synced_blocks.lock().unwrap().get(original_id) {
+            return Ok(children.clone());
+        }
+
+        let nodes = match self
+            .retrieve_block_children_nodes(original_id, None, page_size, report)
+            .await
+        {
+            Ok(nodes) => nodes,
+            Err(NotionClientError::Api(e))
+                if matches!(
+                    e.code,
+                    NotionErrorCode::ObjectNotFound | NotionErrorCode::RestrictedResource
+                ) =>
+            {
+                report.warn(ExportWarning {
+                    kind: WarningKind::ApiError,
+                    block_id: Some(block_id.into()),
+                    block_type: Some("synced_block".into()),
+                    message: format!("original block {} is not accessible: {}", original_id, e),
+                });
+                Vec::new()
+            }
+            Err(e) => return Err(e),
+        };
+
+        let mut children = Vec::with_capacity(nodes.len());
+        for node in nodes {
+            children.push(self.hydrate_block(node, page_size, report).await?);
+        }
+
+        self.synced_blocks
+            .lock()
+            .unwrap()
+            .insert(original_id.into(), children.clone());
+
+        Ok(children)
+    }
+
+    /// Lists the people and bots of the workspace, without guests. The list is retrieved
+    /// once and returned again by later calls.
+    pub async fn list_users(&self) -> Result<Vec<User>, NotionClientError> {
+        if let Some(users) = self.workspace_users.lock().unwrap().as_ref() {
+            return Ok(users.clone());
+        }
+
+        let mut users = Vec::new();
+        let mut cursor: Option<String> = None;
+        loop {
+            let mut query = Vec::new();
+            if let Some(cursor) = cursor.take() {
+                query.push(("start_cursor", cursor));
+            }
+            let resp: ApiUserList = self.get("/v1/users", &query).await?;
+
+            users.extend(resp.results.into_iter().map(User::from));
+            match resp.next_cursor {
+                Some(next) => cursor = Some(next),
+                None => break,
+            }
+        }
+
+        let mut cache = self.users.lock().unwrap();
+        for user in &users {
+            cache.insert(normalize_page_id(&user.id), user.clone());
+        }
+        *self.workspace_users.lock().unwrap() = Some(users.clone());
+
+        Ok(users)
+    }
+
+    /// Retrieves a user, guests included, unless it was already listed or retrieved.
+    pub async fn retrieve_user(&self, user_id: &str) -> Result<User, NotionClientError> {
+        if let Some(user) = self.users.lock().unwrap().get(&normalize_page_id(user_id)) {
+            return Ok(user.clone());
+        }
+
+        let user: User = self
+            .get::<ApiUser>(&format!("/v1/users/{}", user_id), &[])
+            .await?
+            .into();
+        self.users
+            .lock()
+            .unwrap()
+            .insert(normalize_page_id(user_id), user.clone());
+
+        Ok(user)
+    }
+
+    /// Downloads a file referenced by a block, such as the URL of an `image` or `file` block.
+    ///
+    /// Notion-hosted URLs are pre-signed, so no credentials are sent with the request.
+    pub async fn download_file(&self, url: &str) -> Result<Vec<u8>, NotionClientError> {
+        let resp = self.client.get(url).send().await?;
+        let status = resp.status();
+        if !status.is_success() {
+            return Err(NotionClientError::Status(status));
+        }
+
+        Ok(resp.bytes().await?.to_vec())
+    }
+
     pub async fn retrieve_block_children(
         &self,
         block_id: &str,
         initial_cursor: Option<&str>,
         page_size: Option<u32>,
     ) -> Result<Vec<Block>, NotionClientError> {
+        let (blocks, _) = self
+            .retrieve_block_children_with_report(block_id, initial_cursor, page_size)
+            .await?;
+
+        Ok(blocks)
+    }
+
+    /// Same as [`NotionClient::retrieve_block_children`], also returning the blocks that
+    /// could not be deserialized and were rendered as placeholders.
+    pub async fn retrieve_block_children_with_report(
+        &self,
+        block_id: &str,
+        initial_cursor: Option<&str>,
+        page_size: Option<u32>,
+    ) -> Result<(Vec<Block>, ExportReport), NotionClientError> {
+        let mut report = ExportReport::default();
         let children = self
-            .retrieve_block_children_nodes(block_id, initial_cursor, page_size)
+            .retrieve_block_children_nodes(block_id, initial_cursor, page_size, &mut report)
             .await?;
 
         let mut blocks = Vec::with_capacity(children.len());
         for child in children {
-            blocks.push(self.hydrate_block(child, page_size).await?);
+            blocks.push(self.hydrate_block(child, page_size, &mut report).await?);
         }
 
-        Ok(blocks)
+        Ok((blocks, report))
+    }
+
+    /// Retrieves a page with all of its blocks, so it can be saved and rendered later
+    /// without the API.
+    pub async fn retrieve_snapshot(
+        &self,
+        page_id: &str,
+        page_size: Option<u32>,
+    ) -> Result<(Snapshot, ExportReport), NotionClientError> {
+        let page = self.retrieve_page(page_id).await?;
+        let (blocks, report) = self
+            .retrieve_block_children_with_report(page_id, None, page_size)
+            .await?;
+
+        Ok((Snapshot::new(page.into(), blocks), report))
+    }
+
+    /// Appends blocks, such as those built by [`markdown_to_blocks`], to the end of a page or
+    /// block and returns the IDs of the blocks created directly under it.
+    ///
+    /// Requests are split to stay within the API limits of 100 blocks per request and two
+    /// levels of nesting; nested children are appended to their parent once it exists.
+    ///
+    /// [`markdown_to_blocks`]: notion_to_markdown_core::markdown_to_blocks
+    pub async fn append_block_children(
+        &self,
+        block_id: &str,
+        children: Vec<serde_json::Value>,
+    ) -> Result<Vec<String>, NotionClientError> {
+        self.append_block_children_boxed(block_id.to_string(), children)
+            .await
+    }
+
+    fn append_block_children_boxed(
+        &self,
+        block_id: String,
+        children: Vec<serde_json::Value>,
+    ) -> std::pin::Pin<
+        Box<dyn std::future::Future<Output = Result<Vec<String>, NotionClientError>> + '_>,
+    > {
+        Box::pin(async move {
+            let path = format!("/v1/blocks/{}/children", block_id);
+            let mut ids = Vec::with_capacity(children.len());
+
+            let mut children = children.into_iter().peekable();
+            while children.peek().is_some() {
+                let mut chunk = Vec::with_capacity(APPEND_BLOCK_LIMIT);
+                let mut nested = Vec::with_capacity(APPEND_BLOCK_LIMIT);
+                for mut child in children.by_ref().take(APPEND_BLOCK_LIMIT) {
+                    nested.push(take_nested_children(&mut child));
+                    chunk.push(child);
+                }
+
+                let body = serde_json::json!({ "children": chunk });
+                let resp: ApiAppendBlockChildrenResponse =
+                    self.send(Method::PATCH, &path, &[], Some(&body)).await?;
+
+                for (created, nested) in resp.results.into_iter().zip(nested) {
+                    if !nested.is_empty() {
+                        self.append_block_children_boxed(created.id.clone(), nested)
+                            .await?;
+                    }
+                    ids.push(created.id);
+                }
+            }
+
+            Ok(ids)
+        })
+    }
+}
+
+/// Removes the children of a block about to be appended, except where the API
+/// requires them in the same request (table rows).
+fn take_nested_children(block: &mut serde_json::Value) -> Vec<serde_json::Value> {
+    let Some(block_type) = block["type"].as_str().map(String::from) else {
+        return Vec::new();
+    };
+    if block_type == "table" {
+        return Vec::new();
+    }
+
+    match block[block_type.as_str()]
+        .as_object_mut()
+        .and_then(|content| content.remove("children"))
+    {
+        Some(serde_json::Value::Array(children)) => children,
+        _ => Vec::new(),
+    }
+}
+
+/// The wait a `Retry-After` header asks for, in seconds as Notion sends it.
+fn retry_after(headers: &reqwest::header::HeaderMap) -> Option<Duration> {
+    let seconds = headers
+        .get(reqwest::header::RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse()
+        .ok()?;
+    Some(Duration::from_secs(seconds))
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use pretty_assertions::assert_eq;
+    use reqwest::header::{HeaderMap, HeaderValue, RETRY_AFTER};
+
+    use notion_to_markdown_core::Block;
+
+    use crate::{ApiBlock, ExportReport, retry_after};
+
+    #[test]
+    fn test_retry_after() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(retry_after(&headers), None);
+
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("3"));
+        assert_eq!(retry_after(&headers), Some(Duration::from_secs(3)));
+
+        headers.insert(
+            RETRY_AFTER,
+            HeaderValue::from_static("Wed, 21 Oct 2015 07:28:00 GMT"),
+        );
+        assert_eq!(retry_after(&headers), None);
+    }
+
+    #[test]
+    fn test_api_block_from_invalid_value() {
+        let value = serde_json::json!({
+            "object": "block",
+            "id": "XXXXXXXX-XXXX-XXXX-XXXX-XXXXXXXXXXXX",
+            "has_children": true,
+            "type": "image",
+            "image": { "type": "external", "external": { "url": "https://example.com" } }
+        });
+        let mut report = ExportReport::default();
+
+        let block = ApiBlock::from_value(value, &mut report);
+
+        assert!(block.has_children);
+        assert!(matches!(block.block, Block::Unparsed(_)));
+        assert_eq!(
+            block.block.to_markdown(),
+            "<!-- unparsed image block: XXXXXXXX-XXXX-XXXX-XXXX-XXXXXXXXXXXX -->"
+        );
+        assert_eq!(report.warnings.len(), 1);
+        assert_eq!(
+            report.warnings[0].block_id.as_deref(),
+            Some("XXXXXXXX-XXXX-XXXX-XXXX-XXXXXXXXXXXX")
+        );
+        assert_eq!(report.warnings[0].block_type.as_deref(), Some("image"));
+    }
+
+    #[test]
+    fn test_api_block_from_valid_value() {
+        let value = serde_json::json!({
+            "object": "block",
+            "id": "XXXXXXXX-XXXX-XXXX-XXXX-XXXXXXXXXXXX",
+            "has_children": false,
+            "type": "divider",
+            "divider": {}
+        });
+        let mut report = ExportReport::default();
+
+        let block = ApiBlock::from_value(value, &mut report);
+
+        assert!(matches!(block.block, Block::Divider(_)));
+        assert!(report.is_empty());
     }
 }
@@ -1,9 +1,163 @@
-use client::NotionClient;
-use notion_to_markdown_core::BlockChildren;
-use std::{env, fs};
+use client::{
+    DiagramCommand, ExportError, ExportProfile, ExportReport, Exporter, NotionClient,
+    NotionClientError,
+};
+use notion_to_markdown_core::{MarkdownRenderOptions, NamingStrategy, UserDirectory};
+use std::{collections::HashMap, env, fs, process, sync::Arc};
 
-const OUTPUT_PATH: &str = "output.md";
+const OUTPUT_DIR: &str = "output";
 const TOKEN_ENV_VAR: &str = "NOTION_TOKEN";
+const BASE_URL_ENV_VAR: &str = "NOTION_BASE_URL";
+const USAGE: &str = "Usage: client <PAGE_ID> [OUTPUT_DIR] [--full] [--snapshots] \
+    [--render-diagrams] [--profile plain|hugo|mkdocs|docusaurus|mdbook] \
+    [--naming title|slug|title-id|id] [--compact-tables] [--align-tables] [--max-warnings N] \
+    [--user-mentions TEMPLATE] [--user-handles FILE]";
+
+struct Args {
+    page_id: String,
+    output_dir: String,
+    full: bool,
+    snapshots: bool,
+    render_diagrams: bool,
+    profile: ExportProfile,
+    naming: NamingStrategy,
+    compact_tables: bool,
+    align_tables: bool,
+    max_warnings: Option<usize>,
+    /// Template of user mentions, such as `[@{name}](mailto:{email})`.
+    user_mentions: Option<String>,
+    /// JSON file mapping user IDs or email addresses to handles, for `{handle}`.
+    user_handles: Option<String>,
+}
+
+impl Args {
+    fn parse() -> Args {
+        let mut positional = Vec::new();
+        let mut full = false;
+        let mut snapshots = false;
+        let mut render_diagrams = false;
+        let mut profile = ExportProfile::default();
+        let mut naming = NamingStrategy::default();
+        let mut compact_tables = false;
+        let mut align_tables = false;
+        let mut max_warnings = None;
+        let mut user_mentions = None;
+        let mut user_handles = None;
+
+        let mut args = env::args().skip(1);
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--full" => full = true,
+                "--snapshots" => snapshots = true,
+                "--render-diagrams" => render_diagrams = true,
+                "--compact-tables" => compact_tables = true,
+                "--align-tables" => align_tables = true,
+                "--profile" => {
+                    let Some(name) = args.next() else {
+                        exit_with_usage("missing value for --profile");
+                    };
+                    profile = match name.parse::<ExportProfile>() {
+                        Ok(profile) => profile,
+                        Err(e) => exit_with_usage(&e.to_string()),
+                    };
+                }
+                "--naming" => {
+                    naming = match args.next().as_deref() {
+                        Some("title") => NamingStrategy::Title,
+                        Some("slug") => NamingStrategy::Slug,
+                        Some("title-id") => NamingStrategy::TitleWithId,
+                        Some("id") => NamingStrategy::Id,
+                        Some(name) => exit_with_usage(&format!("unknown naming {}", name)),
+                        None => exit_with_usage("missing value for --naming"),
+                    };
+                }
+                "--max-warnings" => {
+                    max_warnings = match args.next().map(|max| max.parse::<usize>()) {
+                        Some(Ok(max)) => Some(max),
+                        Some(Err(e)) => exit_with_usage(&format!("invalid --max-warnings: {}", e)),
+                        None => exit_with_usage("missing value for --max-warnings"),
+                    };
+                }
+                "--user-mentions" => {
+                    let Some(template) = args.next() else {
+                        exit_with_usage("missing value for --user-mentions");
+                    };
+                    user_mentions = Some(template);
+                }
+                "--user-handles" => {
+                    let Some(path) = args.next() else {
+                        exit_with_usage("missing value for --user-handles");
+                    };
+                    user_handles = Some(path);
+                }
+                "-h" | "--help" => {
+                    println!("{}", USAGE);
+                    process::exit(0);
+                }
+                _ if arg.starts_with('-') => exit_with_usage(&format!("unknown option {}", arg)),
+                _ => positional.push(arg),
+            }
+        }
+
+        let mut positional = positional.into_iter();
+        let Some(page_id) = positional.next() else {
+            exit_with_usage("missing PAGE_ID");
+        };
+        let output_dir = positional.next().unwrap_or_else(|| OUTPUT_DIR.into());
+        if positional.next().is_some() {
+            exit_with_usage("too many arguments");
+        }
+
+        Args {
+            page_id,
+            output_dir,
+            full,
+            snapshots,
+            render_diagrams,
+            profile,
+            naming,
+            compact_tables,
+            align_tables,
+            max_warnings,
+            user_mentions,
+            user_handles,
+        }
+    }
+
+    fn render_options(&self) -> MarkdownRenderOptions {
+        let defaults = self.profile.render_options();
+        MarkdownRenderOptions {
+            compact_tables: self.compact_tables,
+            infer_table_alignment: self.align_tables,
+            users: self
+                .user_handles
+                .as_deref()
+                .map(|path| Arc::new(load_user_handles(path))),
+            user_mention_template: self
+                .user_mentions
+                .clone()
+                .unwrap_or_else(|| defaults.user_mention_template.clone()),
+            ..defaults
+        }
+    }
+}
+
+/// Reads a JSON object of handles keyed by user ID or email address.
+fn load_user_handles(path: &str) -> UserDirectory {
+    let handles: HashMap<String, String> = match fs::read(path)
+        .map_err(|e| e.to_string())
+        .and_then(|bytes| serde_json::from_slice(&bytes).map_err(|e| e.to_string()))
+    {
+        Ok(handles) => handles,
+        Err(e) => exit_with_usage(&format!("invalid --user-handles {}: {}", path, e)),
+    };
+
+    let mut users = UserDirectory::new();
+    for (user, handle) in handles {
+        users.insert_handle(&user, handle);
+    }
+    users
+}
 
 #[cfg(feature = "log")]
 fn init_cli_environment() {
@@ -14,17 +168,63 @@ fn init_cli_environment() {
 #[cfg(not(feature = "log"))]
 fn init_cli_environment() {}
 
+fn exit_with_usage(message: &str) -> ! {
+    eprintln!("Error: {}", message);
+    eprintln!("{}", USAGE);
+    process::exit(2);
+}
+
+fn print_summary(report: &ExportReport) {
+    for warning in &report.warnings {
+        eprintln!("Warning: {}", warning);
+    }
+    println!("{}", report);
+}
+
+fn exit_with_error(err: ExportError) -> ! {
+    if let ExportError::TooManyWarnings { report, .. } = &err {
+        print_summary(report);
+    }
+    eprintln!("Error: {}", err);
+    if let ExportError::Client(NotionClientError::Api(e)) = &err
+        && let Some(hint) = e.hint()
+    {
+        eprintln!("Hint: {}", hint);
+    }
+    process::exit(1);
+}
+
 #[tokio::main(flavor = "current_thread")]
 async fn main() {
     init_cli_environment();
+    let args = Args::parse();
     let token = env::var(TOKEN_ENV_VAR).expect("NOTION_TOKEN is not set");
 
-    let markdown = NotionClient::new(token)
-        .retrieve_block_children("2f853222ff1e80829678eeb55e7add95".into(), None, None)
-        .await
-        .unwrap()
-        .to_markdown(0);
+    let mut builder = NotionClient::builder(token);
+    if let Ok(base_url) = env::var(BASE_URL_ENV_VAR) {
+        builder = builder.base_url(base_url);
+    }
+    let client = builder.build().unwrap();
+
+    let mut exporter = Exporter::new(&client, &args.output_dir)
+        .profile(args.profile)
+        .options(args.render_options())
+        .resolve_users(args.user_mentions.is_some() || args.user_handles.is_some())
+        .naming(args.naming)
+        .incremental(!args.full)
+        .snapshots(args.snapshots);
+    if let Some(max) = args.max_warnings {
+        exporter = exporter.max_warnings(max);
+    }
+    if args.render_diagrams {
+        exporter = exporter.diagram_command(DiagramCommand::mermaid_cli());
+    }
+
+    let report = match exporter.export(&args.page_id).await {
+        Ok(report) => report,
+        Err(e) => exit_with_error(e),
+    };
 
-    fs::write(OUTPUT_PATH, markdown).unwrap();
-    println!("Wrote markdown to {}", OUTPUT_PATH);
+    print_summary(&report);
+    println!("Manifest written to {}/manifest.json", args.output_dir);
 }
@@ -0,0 +1,56 @@
+use serde::{Deserialize, Serialize};
+use std::{fs, io, path::Path};
+
+use crate::ExportWarning;
+
+/// Name of the file, inside the output directory, listing what the export contains.
+pub(crate) const MANIFEST_FILE_NAME: &str = "manifest.json";
+
+/// Machine-readable account of an export, written as `manifest.json` next to the pages.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Manifest {
+    /// ID of the page the export started from.
+    pub root_page_id: String,
+    /// Every exported page, in the order they were found in.
+    pub pages: Vec<ManifestPage>,
+    /// Paths of files deleted because their page is gone, relative to the output directory.
+    pub removed: Vec<String>,
+    /// Problems not tied to an exported page, such as nested pages that could not be
+    /// retrieved.
+    pub warnings: Vec<ExportWarning>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ManifestPage {
+    pub id: String,
+    pub title: String,
+    /// Path of the Markdown file, relative to the output directory.
+    pub path: String,
+    pub last_edited_time: String,
+    pub status: PageStatus,
+    /// Files written next to the page, such as rendered diagrams.
+    pub assets: Vec<String>,
+    /// Blocks of the page that were skipped or could not be retrieved. For unchanged pages,
+    /// those found when the page was last written.
+    pub warnings: Vec<ExportWarning>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PageStatus {
+    Written,
+    Unchanged,
+}
+
+impl Manifest {
+    /// Reads the manifest an export left in `output_dir`.
+    pub fn load(output_dir: &Path) -> io::Result<Manifest> {
+        let bytes = fs::read(output_dir.join(MANIFEST_FILE_NAME))?;
+        serde_json::from_slice(&bytes).map_err(io::Error::other)
+    }
+
+    pub(crate) fn save(&self, output_dir: &Path) -> io::Result<()> {
+        let json = serde_json::to_vec_pretty(self).map_err(io::Error::other)?;
+        fs::write(output_dir.join(MANIFEST_FILE_NAME), json)
+    }
+}
@@ -0,0 +1,117 @@
+use notion_to_markdown_core::SnapshotPage;
+use std::collections::HashMap;
+
+/// The parts of a Notion page object the exporter needs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Page {
+    pub id: String,
+    pub title: String,
+    pub last_edited_time: String,
+    /// Set when the page was archived or moved to the trash.
+    pub archived: bool,
+}
+
+impl From<Page> for SnapshotPage {
+    fn from(page: Page) -> Self {
+        SnapshotPage {
+            id: page.id,
+            title: page.title,
+            last_edited_time: page.last_edited_time,
+        }
+    }
+}
+
+#[derive(serde::Deserialize)]
+pub(crate) struct ApiPage {
+    id: String,
+    last_edited_time: String,
+    #[serde(default)]
+    archived: bool,
+    #[serde(default)]
+    in_trash: bool,
+    #[serde(default)]
+    properties: HashMap<String, serde_json::Value>,
+}
+
+impl ApiPage {
+    /// Plain text of the page's `title` property, whatever the property is named.
+    fn title(&self) -> String {
+        self.properties
+            .values()
+            .find(|property| property["type"] == "title")
+            .and_then(|property| property["title"].as_array())
+            .map(|rich_text| {
+                rich_text
+                    .iter()
+                    .filter_map(|item| item["plain_text"].as_str())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+#[derive(serde::Deserialize)]
+pub(crate) struct ApiDatabase {
+    #[serde(default)]
+    title: Vec<serde_json::Value>,
+}
+
+impl ApiDatabase {
+    pub(crate) fn title(&self) -> String {
+        self.title
+            .iter()
+            .filter_map(|item| item["plain_text"].as_str())
+            .collect()
+    }
+}
+
+impl From<ApiPage> for Page {
+    fn from(page: ApiPage) -> Self {
+        Page {
+            title: page.title(),
+            archived: page.archived || page.in_trash,
+            id: page.id,
+            last_edited_time: page.last_edited_time,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use super::{ApiPage, Page};
+
+    #[test]
+    fn test_from_api_page() {
+        let page: ApiPage = serde_json::from_value(serde_json::json!({
+            "object": "page",
+            "id": "XXXXXXXX-XXXX-XXXX-XXXX-XXXXXXXXXXXX",
+            "last_edited_time": "2000-01-01T00:00:00.000Z",
+            "archived": false,
+            "in_trash": true,
+            "properties": {
+                "Status": { "id": "a", "type": "select", "select": null },
+                "Name": {
+                    "id": "title",
+                    "type": "title",
+                    "title": [
+                        { "type": "text", "plain_text": "this is " },
+                        { "type": "text", "plain_text": "page" }
+                    ]
+                }
+            }
+        }))
+        .unwrap();
+
+        assert_eq!(
+            Page::from(page),
+            Page {
+                id: "XXXXXXXX-XXXX-XXXX-XXXX-XXXXXXXXXXXX".into(),
+                title: "this is page".into(),
+                last_edited_time: "2000-01-01T00:00:00.000Z".into(),
+                archived: true,
+            }
+        );
+    }
+}
@@ -0,0 +1,658 @@
+use notion_to_markdown_core::{
+    CalloutStyle, ChildLinkTarget, MarkdownRenderOptions, MathDelimiters, MermaidTarget,
+};
+use std::{error, fmt, str::FromStr};
+
+/// Conventions of the static site generator an export is published with: how blocks are
+/// rendered, where pages and assets are written and which navigation files go with them.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ExportProfile {
+    /// Every page as `{title}.md` in the output directory, without front matter.
+    #[default]
+    Plain,
+    /// Pages under `content/` as bundles, `_index.md` for sections.
+    Hugo,
+    /// Pages under `docs/` with `index.md` for sections, and the nav in `mkdocs.yml`.
+    /// An existing `mkdocs.yml` only gets its nav replaced, so it needs Material's
+    /// `navigation.indexes`, the `admonition` and `pymdownx.arithmatex` extensions and
+    /// `pymdownx.superfences` with a `mermaid` custom fence for the pages to render as
+    /// intended.
+    MkDocs,
+    /// Pages under `docs/` with `index.md` and a `_category_.json` for sections.
+    Docusaurus,
+    /// Pages under `src/` with `README.md` for sections, listed in `SUMMARY.md`, and a
+    /// `book.toml` unless there is one.
+    MdBook,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct ParseProfileError(String);
+
+impl fmt::Display for ParseProfileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "unknown profile {}, expected one of plain, hugo, mkdocs, docusaurus, mdbook",
+            self.0
+        )
+    }
+}
+
+impl error::Error for ParseProfileError {}
+
+impl FromStr for ExportProfile {
+    type Err = ParseProfileError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "plain" => Ok(ExportProfile::Plain),
+            "hugo" => Ok(ExportProfile::Hugo),
+            "mkdocs" => Ok(ExportProfile::MkDocs),
+            "docusaurus" => Ok(ExportProfile::Docusaurus),
+            "mdbook" => Ok(ExportProfile::MdBook),
+            _ => Err(ParseProfileError(s.into())),
+        }
+    }
+}
+
+/// A page placed in the navigation, with the pages nested in it in Notion's order.
+pub(crate) struct NavEntry<'a> {
+    pub title: &'a str,
+    pub path: &'a str,
+    pub children: Vec<NavEntry<'a>>,
+}
+
+impl ExportProfile {
+    pub fn render_options(self) -> MarkdownRenderOptions {
+        let (callout_style, math_delimiters, mermaid_target) = match self {
+            ExportProfile::Plain => (
+                CalloutStyle::Blockquote,
+                MathDelimiters::Dollars,
+                MermaidTarget::GitHub,
+            ),
+            ExportProfile::Hugo => (
+                CalloutStyle::GitHubAlert,
+                MathDelimiters::Dollars,
+                MermaidTarget::GitHub,
+            ),
+            ExportProfile::MkDocs => (
+                CalloutStyle::MkDocs,
+                MathDelimiters::Dollars,
+                MermaidTarget::MkDocs,
+            ),
+            ExportProfile::Docusaurus => (
+                CalloutStyle::Docusaurus,
+                MathDelimiters::Dollars,
+                MermaidTarget::Docusaurus,
+            ),
+            ExportProfile::MdBook => (
+                CalloutStyle::Blockquote,
+                MathDelimiters::EscapedBrackets,
+                MermaidTarget::GitHub,
+            ),
+        };
+
+        MarkdownRenderOptions {
+            child_page_link_target: ChildLinkTarget::MarkdownFile,
+            callout_style,
+            math_delimiters,
+            mermaid_target,
+            ..Default::default()
+        }
+    }
+
+    /// Directory the pages are written to, relative to the output directory.
+    pub(crate) fn content_dir(self) -> &'static str {
+        match self {
+            ExportProfile::Plain => "",
+            ExportProfile::Hugo => "content",
+            ExportProfile::MkDocs | ExportProfile::Docusaurus => "docs",
+            ExportProfile::MdBook => "src",
+        }
+    }
+
+    /// File a page with nested pages is written to, inside a directory named after it.
+    /// `None` when every page is written to one flat directory.
+    fn index_file_name(self) -> Option<&'static str> {
+        match self {
+            ExportProfile::Plain => None,
+            ExportProfile::Hugo => Some("_index.md"),
+            ExportProfile::MkDocs | ExportProfile::Docusaurus => Some("index.md"),
+            ExportProfile::MdBook => Some("README.md"),
+        }
+    }
+
+    /// Directory a page nested in the page at `parent_path` is named in; page names have to
+    /// be unique within it.
+    pub(crate) fn name_dir(self, parent_path: Option<&str>) -> &str {
+        match (self.index_file_name(), parent_path) {
+            (Some(_), Some(parent_path)) => parent_dir(parent_path),
+            _ => "",
+        }
+    }
+
+    /// Names pages cannot take because the profile writes other files under them.
+    pub(crate) fn reserved_names(self) -> &'static [&'static str] {
+        match self {
+            ExportProfile::Plain => &[],
+            ExportProfile::Hugo => &["_index", "index"],
+            ExportProfile::MkDocs | ExportProfile::Docusaurus => &["index", "assets"],
+            ExportProfile::MdBook => &["README", "SUMMARY", "assets"],
+        }
+    }
+
+    /// Path of a page relative to the output directory. `parent_path` is the path of the
+    /// page it is nested in, `None` for the exported page itself.
+    pub(crate) fn page_path(
+        self,
+        parent_path: Option<&str>,
+        name: &str,
+        has_children: bool,
+    ) -> String {
+        let Some(index) = self.index_file_name() else {
+            return format!("{}.md", name);
+        };
+        let Some(parent_path) = parent_path else {
+            return join(self.content_dir(), index);
+        };
+
+        let dir = join(parent_dir(parent_path), name);
+        if has_children {
+            join(&dir, index)
+        } else if self == ExportProfile::Hugo {
+            // A leaf bundle, so assets can sit next to the page.
+            join(&dir, "index.md")
+        } else {
+            format!("{}.md", dir)
+        }
+    }
+
+    /// Path of a file belonging to the page at `page_path`, such as a rendered diagram.
+    pub(crate) fn asset_path(self, page_path: &str, suffix: &str) -> String {
+        let stem = page_path.strip_suffix(".md").unwrap_or(page_path);
+        match self {
+            ExportProfile::Plain | ExportProfile::Hugo => format!("{}-{}", stem, suffix),
+            _ => {
+                let stem = stem
+                    .strip_prefix(self.content_dir())
+                    .unwrap_or(stem)
+                    .trim_start_matches('/')
+                    .replace('/', "-");
+                join(
+                    &join(self.content_dir(), "assets"),
+                    &format!("{}-{}", stem, suffix),
+                )
+            }
+        }
+    }
+
+    /// Front matter put at the top of a page, empty where the generator doesn't read any.
+    /// `position` is the place of the page among its siblings, starting at 1.
+    pub(crate) fn front_matter(
+        self,
+        title: &str,
+        last_edited_time: &str,
+        position: usize,
+    ) -> String {
+        let title = yaml_string(title);
+        let fields = match self {
+            ExportProfile::Plain | ExportProfile::MdBook => return String::new(),
+            ExportProfile::Hugo => format!(
+                "title: {}\nweight: {}\nlastmod: {}\n",
+                title, position, last_edited_time
+            ),
+            ExportProfile::MkDocs => format!("title: {}\n", title),
+            ExportProfile::Docusaurus => {
+                format!("title: {}\nsidebar_position: {}\n", title, position)
+            }
+        };
+
+        format!("---\n{}---\n\n", fields)
+    }
+
+    /// Navigation files for `root`, as paths relative to the output directory and their
+    /// content. `existing` reads a file left by a previous export or by hand.
+    pub(crate) fn nav_files(
+        self,
+        root: &NavEntry,
+        existing: impl Fn(&str) -> Option<String>,
+    ) -> Vec<(String, String)> {
+        match self {
+            ExportProfile::Plain | ExportProfile::Hugo => Vec::new(),
+            ExportProfile::MdBook => {
+                let mut summary = format!(
+                    "# Summary\n\n[{}]({})\n\n",
+                    escape_link_text(root.title),
+                    link_destination(self.docs_relative(root.path))
+                );
+                for child in &root.children {
+                    self.mdbook_summary(child, 0, &mut summary);
+                }
+
+                let mut files = vec![(join(self.content_dir(), "SUMMARY.md"), summary)];
+                if existing("book.toml").is_none() {
+                    files.push(("book.toml".into(), self.book_toml(root.title)));
+                }
+                files
+            }
+            ExportProfile::MkDocs => {
+                let mut nav = String::from("nav:\n");
+                nav.push_str(&format!(
+                    "  - {}: {}\n",
+                    yaml_string(root.title),
+                    yaml_string(self.docs_relative(root.path))
+                ));
+                for child in &root.children {
+                    self.mkdocs_nav(child, 1, &mut nav);
+                }
+
+                let config = match existing("mkdocs.yml") {
+                    Some(config) => replace_nav(&config, &nav),
+                    None => self.mkdocs_yml(root.title) + &nav,
+                };
+                vec![("mkdocs.yml".into(), config)]
+            }
+            ExportProfile::Docusaurus => {
+                let mut files = Vec::new();
+                docusaurus_categories(root, &mut files);
+                files
+            }
+        }
+    }
+
+    fn docs_relative(self, path: &str) -> &str {
+        path.strip_prefix(self.content_dir())
+            .unwrap_or(path)
+            .trim_start_matches('/')
+    }
+
+    fn mkdocs_nav(self, entry: &NavEntry, depth: usize, nav: &mut String) {
+        let indent = "  ".repeat(depth);
+        let path = yaml_string(self.docs_relative(entry.path));
+        if entry.children.is_empty() {
+            nav.push_str(&format!(
+                "{}- {}: {}\n",
+                indent,
+                yaml_string(entry.title),
+                path
+            ));
+            return;
+        }
+
+        // A section whose first entry is its index page, as `navigation.indexes` expects.
+        nav.push_str(&format!("{}- {}:\n", indent, yaml_string(entry.title)));
+        nav.push_str(&format!("{}  - {}\n", indent, path));
+        for child in &entry.children {
+            self.mkdocs_nav(child, depth + 1, nav);
+        }
+    }
+
+    fn mdbook_summary(self, entry: &NavEntry, depth: usize, summary: &mut String) {
+        summary.push_str(&format!(
+            "{}- [{}]({})\n",
+            "  ".repeat(depth),
+            escape_link_text(entry.title),
+            link_destination(self.docs_relative(entry.path))
+        ));
+        for child in &entry.children {
+            self.mdbook_summary(child, depth + 1, summary);
+        }
+    }
+
+    /// A minimal `mkdocs.yml` for the exported pages, without the nav. Material for MkDocs
+    /// reads section index pages with `navigation.indexes`, and the extensions render the
+    /// admonitions, diagrams and math the profile writes.
+    fn mkdocs_yml(self, title: &str) -> String {
+        format!(
+            concat!(
+                "site_name: {}\n",
+                "theme:\n",
+                "  name: material\n",
+                "  features:\n",
+                "    - navigation.indexes\n",
+                "markdown_extensions:\n",
+                "  - admonition\n",
+                "  - pymdownx.superfences:\n",
+                "      custom_fences:\n",
+                "        - name: mermaid\n",
+                "          class: mermaid\n",
+                "          format: !!python/name:pymdownx.superfences.fence_code_format\n",
+                "  - pymdownx.arithmatex:\n",
+                "      generic: true\n",
+                "extra_javascript:\n",
+                "  - https://unpkg.com/mathjax@3/es5/tex-mml-chtml.js\n",
+            ),
+            yaml_string(title)
+        )
+    }
+
+    /// A minimal `book.toml` for the exported pages, with MathJax for equation blocks.
+    fn book_toml(self, title: &str) -> String {
+        // JSON strings are valid TOML basic strings as well.
+        format!(
+            "[book]\ntitle = {}\nsrc = \"{}\"\n\n[output.html]\nmathjax-support = true\n",
+            serde_json::Value::from(title),
+            self.content_dir()
+        )
+    }
+}
+
+fn docusaurus_categories(entry: &NavEntry, files: &mut Vec<(String, String)>) {
+    for (index, child) in entry.children.iter().enumerate() {
+        if !child.children.is_empty() {
+            let category = serde_json::json!({ "label": child.title, "position": index + 1 });
+            files.push((
+                join(parent_dir(child.path), "_category_.json"),
+                format!("{:#}\n", category),
+            ));
+        }
+        docusaurus_categories(child, files);
+    }
+}
+
+/// Replaces the top-level `nav:` section of a MkDocs config, or appends one.
+fn replace_nav(config: &str, nav: &str) -> String {
+    let mut lines = config.lines().peekable();
+    let mut replaced = String::new();
+    let mut found = false;
+
+    while let Some(line) = lines.next() {
+        if !found && line.starts_with("nav:") {
+            found = true;
+            replaced.push_str(nav);
+            while lines
+                .peek()
+                .is_some_and(|line| line.is_empty() || line.starts_with([' ', '-', '\t']))
+            {
+                lines.next();
+            }
+            continue;
+        }
+        replaced.push_str(line);
+        replaced.push('\n');
+    }
+
+    if !found {
+        replaced.push_str(nav);
+    }
+    replaced
+}
+
+/// Escapes the brackets that would end the text of a Markdown link.
+fn escape_link_text(text: &str) -> String {
+    text.replace('[', "\\[").replace(']', "\\]")
+}
+
+/// `path` as a link destination, in angle brackets when it contains characters such as
+/// spaces, parentheses or `#` that would otherwise end it or start a fragment. mdBook reads
+/// the destination as a file path, so it isn't percent-encoded.
+fn link_destination(path: &str) -> String {
+    if path
+        .chars()
+        .all(|c| c.is_alphanumeric() || matches!(c, '/' | '.' | '-' | '_'))
+    {
+        return path.into();
+    }
+
+    let mut destination = String::from("<");
+    for c in path.chars() {
+        if matches!(c, '<' | '>' | '\\') {
+            destination.push('\\');
+        }
+        destination.push(c);
+    }
+    destination.push('>');
+    destination
+}
+
+/// JSON strings are valid YAML scalars and need no further escaping.
+fn yaml_string(text: &str) -> String {
+    serde_json::Value::from(text).to_string()
+}
+
+fn parent_dir(path: &str) -> &str {
+    path.rsplit_once('/').map(|(dir, _)| dir).unwrap_or("")
+}
+
+fn join(dir: &str, name: &str) -> String {
+    if dir.is_empty() {
+        name.into()
+    } else {
+        format!("{}/{}", dir, name)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    use super::{ExportProfile, NavEntry, replace_nav};
+
+    fn tree() -> NavEntry<'static> {
+        NavEntry {
+            title: "Handbook",
+            path: "docs/index.md",
+            children: vec![
+                NavEntry {
+                    title: "Teams",
+                    path: "docs/Teams/index.md",
+                    children: vec![NavEntry {
+                        title: "Design",
+                        path: "docs/Teams/Design.md",
+                        children: Vec::new(),
+                    }],
+                },
+                NavEntry {
+                    title: "FAQ",
+                    path: "docs/FAQ.md",
+                    children: Vec::new(),
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_page_path() {
+        assert_eq!(
+            ExportProfile::Plain.page_path(None, "Root", true),
+            "Root.md"
+        );
+        assert_eq!(
+            ExportProfile::Plain.page_path(Some("Root.md"), "Child", false),
+            "Child.md"
+        );
+
+        assert_eq!(
+            ExportProfile::Hugo.page_path(None, "Root", true),
+            "content/_index.md"
+        );
+        assert_eq!(
+            ExportProfile::Hugo.page_path(Some("content/_index.md"), "Teams", true),
+            "content/Teams/_index.md"
+        );
+        assert_eq!(
+            ExportProfile::Hugo.page_path(Some("content/Teams/_index.md"), "Design", false),
+            "content/Teams/Design/index.md"
+        );
+
+        assert_eq!(
+            ExportProfile::MdBook.page_path(Some("src/README.md"), "Teams", true),
+            "src/Teams/README.md"
+        );
+        assert_eq!(
+            ExportProfile::MkDocs.page_path(Some("docs/Teams/index.md"), "Design", false),
+            "docs/Teams/Design.md"
+        );
+    }
+
+    #[test]
+    fn test_asset_path() {
+        assert_eq!(
+            ExportProfile::Plain.asset_path("Page.md", "diagram-1.svg"),
+            "Page-diagram-1.svg"
+        );
+        assert_eq!(
+            ExportProfile::Hugo.asset_path("content/Page/index.md", "diagram-1.svg"),
+            "content/Page/index-diagram-1.svg"
+        );
+        assert_eq!(
+            ExportProfile::MkDocs.asset_path("docs/Teams/Design.md", "diagram-1.svg"),
+            "docs/assets/Teams-Design-diagram-1.svg"
+        );
+    }
+
+    #[test]
+    fn test_front_matter() {
+        assert_eq!(
+            ExportProfile::Docusaurus.front_matter("Q: \"why\"", "2000-01-01T00:00:00.000Z", 2),
+            indoc! {r#"
+                ---
+                title: "Q: \"why\""
+                sidebar_position: 2
+                ---
+
+            "#}
+        );
+        assert_eq!(
+            ExportProfile::MdBook.front_matter("Page", "2000-01-01T00:00:00.000Z", 1),
+            ""
+        );
+    }
+
+    #[test]
+    fn test_mkdocs_nav() {
+        let files = ExportProfile::MkDocs.nav_files(&tree(), |_| None);
+
+        assert_eq!(
+            files,
+            vec![(
+                "mkdocs.yml".to_string(),
+                indoc! {r#"
+                    site_name: "Handbook"
+                    theme:
+                      name: material
+                      features:
+                        - navigation.indexes
+                    markdown_extensions:
+                      - admonition
+                      - pymdownx.superfences:
+                          custom_fences:
+                            - name: mermaid
+                              class: mermaid
+                              format: !!python/name:pymdownx.superfences.fence_code_format
+                      - pymdownx.arithmatex:
+                          generic: true
+                    extra_javascript:
+                      - https://unpkg.com/mathjax@3/es5/tex-mml-chtml.js
+                    nav:
+                      - "Handbook": "index.md"
+                      - "Teams":
+                        - "Teams/index.md"
+                        - "Design": "Teams/Design.md"
+                      - "FAQ": "FAQ.md"
+                "#}
+                .to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn test_replace_nav() {
+        let config = indoc! {r#"
+            site_name: Handbook
+            nav:
+              - Old: old.md
+
+            theme:
+              name: material
+        "#};
+
+        assert_eq!(
+            replace_nav(config, "nav:\n  - New: new.md\n"),
+            indoc! {r#"
+                site_name: Handbook
+                nav:
+                  - New: new.md
+                theme:
+                  name: material
+            "#}
+        );
+    }
+
+    #[test]
+    fn test_mdbook_summary() {
+        let tree = NavEntry {
+            title: "Handbook",
+            path: "src/README.md",
+            children: vec![
+                NavEntry {
+                    title: "Teams [internal]",
+                    path: "src/Teams/README.md",
+                    children: vec![NavEntry {
+                        title: "Design",
+                        path: "src/Teams/Design.md",
+                        children: Vec::new(),
+                    }],
+                },
+                NavEntry {
+                    title: "FAQ #1 (100%)",
+                    path: "src/FAQ_#1_(100%).md",
+                    children: Vec::new(),
+                },
+            ],
+        };
+
+        let files = ExportProfile::MdBook.nav_files(&tree, |_| None);
+
+        assert_eq!(
+            files,
+            vec![
+                (
+                    "src/SUMMARY.md".to_string(),
+                    indoc! {r#"
+                        # Summary
+
+                        [Handbook](README.md)
+
+                        - [Teams \[internal\]](Teams/README.md)
+                          - [Design](Teams/Design.md)
+                        - [FAQ #1 (100%)](<FAQ_#1_(100%).md>)
+                    "#}
+                    .to_string()
+                ),
+                (
+                    "book.toml".to_string(),
+                    indoc! {r#"
+                        [book]
+                        title = "Handbook"
+                        src = "src"
+
+                        [output.html]
+                        mathjax-support = true
+                    "#}
+                    .to_string()
+                ),
+            ]
+        );
+        assert_eq!(
+            ExportProfile::MdBook
+                .nav_files(&tree, |_| Some(String::new()))
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_docusaurus_categories() {
+        let files = ExportProfile::Docusaurus.nav_files(&tree(), |_| None);
+
+        assert_eq!(
+            files,
+            vec![(
+                "docs/Teams/_category_.json".to_string(),
+                "{\n  \"label\": \"Teams\",\n  \"position\": 1\n}\n".to_string()
+            )]
+        );
+    }
+}
@@ -0,0 +1,180 @@
+use serde::{Deserialize, Serialize};
+use std::{fmt, path::PathBuf};
+
+/// What an export did, including problems that did not abort it.
+#[derive(Debug, Default, Clone)]
+pub struct ExportReport {
+    pub warnings: Vec<ExportWarning>,
+    /// Files written because their page is new or changed.
+    pub written: Vec<PathBuf>,
+    /// Files kept as they were because their page did not change since the last run.
+    pub unchanged: Vec<PathBuf>,
+    /// Files deleted because their page was removed, archived or moved.
+    pub removed: Vec<PathBuf>,
+}
+
+impl ExportReport {
+    pub(crate) fn warn(&mut self, warning: ExportWarning) {
+        #[cfg(feature = "log")]
+        log::warn!("{}", warning);
+
+        self.warnings.push(warning);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.warnings.is_empty()
+    }
+
+    /// Number of warnings of `kind`.
+    pub fn count(&self, kind: WarningKind) -> usize {
+        self.warnings.iter().filter(|w| w.kind == kind).count()
+    }
+
+    pub(crate) fn merge(&mut self, other: ExportReport) {
+        self.warnings.extend(other.warnings);
+        self.written.extend(other.written);
+        self.unchanged.extend(other.unchanged);
+        self.removed.extend(other.removed);
+    }
+}
+
+/// Summary of the export for people, one line for files and one for warnings.
+impl fmt::Display for ExportReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} written, {} unchanged, {} removed",
+            plural(self.written.len(), "page", "pages"),
+            self.unchanged.len(),
+            self.removed.len()
+        )?;
+
+        if self.warnings.is_empty() {
+            return write!(f, "no warnings");
+        }
+        let kinds: Vec<String> = WarningKind::ALL
+            .into_iter()
+            .map(|kind| (kind, self.count(kind)))
+            .filter(|(_, count)| *count > 0)
+            .map(|(kind, count)| kind.describe(count))
+            .collect();
+        write!(
+            f,
+            "{}: {}",
+            plural(self.warnings.len(), "warning", "warnings"),
+            kinds.join(", ")
+        )
+    }
+}
+
+fn plural(count: usize, singular: &str, plural: &str) -> String {
+    format!("{} {}", count, if count == 1 { singular } else { plural })
+}
+
+/// What kind of problem a warning is about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WarningKind {
+    /// A block the API returned that could not be deserialized.
+    UnparsedBlock,
+    /// A block the API reports as `unsupported`, or whose type the renderer doesn't know.
+    UnsupportedBlock,
+    /// A page or block that could not be retrieved, such as one not shared with the
+    /// integration.
+    ApiError,
+    /// A diagram the diagram command failed on.
+    Diagram,
+    /// A column list written in another layout than the one asked for, such as one that
+    /// can't be a table.
+    Layout,
+}
+
+impl WarningKind {
+    const ALL: [WarningKind; 5] = [
+        WarningKind::UnparsedBlock,
+        WarningKind::UnsupportedBlock,
+        WarningKind::ApiError,
+        WarningKind::Diagram,
+        WarningKind::Layout,
+    ];
+
+    fn describe(self, count: usize) -> String {
+        match self {
+            WarningKind::UnparsedBlock => plural(count, "unparsed block", "unparsed blocks"),
+            WarningKind::UnsupportedBlock => {
+                plural(count, "unsupported block", "unsupported blocks")
+            }
+            WarningKind::ApiError => plural(count, "API error", "API errors"),
+            WarningKind::Diagram => plural(count, "failed diagram", "failed diagrams"),
+            WarningKind::Layout => plural(count, "layout fallback", "layout fallbacks"),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ExportWarning {
+    pub kind: WarningKind,
+    pub block_id: Option<String>,
+    pub block_type: Option<String>,
+    pub message: String,
+}
+
+impl fmt::Display for ExportWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Not about a block, such as users that could not be listed.
+        if self.block_id.is_none() && self.block_type.is_none() {
+            return write!(f, "{}", self.message);
+        }
+
+        write!(
+            f,
+            "skipped {} block {}: {}",
+            self.block_type.as_deref().unwrap_or("unknown"),
+            self.block_id.as_deref().unwrap_or("without id"),
+            self.message
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn warning(kind: WarningKind) -> ExportWarning {
+        ExportWarning {
+            kind,
+            block_id: None,
+            block_type: None,
+            message: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_summary() {
+        let report = ExportReport {
+            warnings: vec![
+                warning(WarningKind::UnsupportedBlock),
+                warning(WarningKind::ApiError),
+                warning(WarningKind::UnsupportedBlock),
+            ],
+            written: vec![PathBuf::from("a.md")],
+            unchanged: vec![PathBuf::from("b.md"), PathBuf::from("c.md")],
+            ..Default::default()
+        };
+
+        assert_eq!(
+            report.to_string(),
+            "1 page written, 2 unchanged, 0 removed\n\
+             3 warnings: 2 unsupported blocks, 1 API error"
+        );
+    }
+
+    #[test]
+    fn test_summary_without_warnings() {
+        assert_eq!(
+            ExportReport::default().to_string(),
+            "0 pages written, 0 unchanged, 0 removed\nno warnings"
+        );
+    }
+}
@@ -0,0 +1,49 @@
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fs, io, path::Path};
+
+use crate::ExportWarning;
+
+/// Name of the file, inside the output directory, recording what the last export wrote.
+pub(crate) const STATE_FILE_NAME: &str = ".notion-to-markdown.json";
+
+#[derive(Serialize, Deserialize, Default, Debug)]
+pub(crate) struct ExportState {
+    pub pages: BTreeMap<String, PageState>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub(crate) struct PageState {
+    pub title: String,
+    /// Path of the Markdown file, relative to the output directory.
+    pub path: String,
+    pub last_edited_time: String,
+    /// IDs of the pages nested in this one, so they can be visited without fetching its blocks.
+    pub child_pages: Vec<String>,
+    /// IDs of the pages this one links to, without dashes, so it is written again when one
+    /// of them moves. `None` in states written before links were recorded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub links: Option<Vec<String>>,
+    /// Files written next to the page, such as rendered diagrams, relative to the output
+    /// directory.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub assets: Vec<String>,
+    /// Problems found when the page was last written, reported again while it is unchanged.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<ExportWarning>,
+}
+
+impl ExportState {
+    /// Reads the state left by the previous export. A missing or unreadable state file
+    /// yields an empty state, which makes the next export a full one.
+    pub fn load(output_dir: &Path) -> ExportState {
+        fs::read(output_dir.join(STATE_FILE_NAME))
+            .ok()
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, output_dir: &Path) -> io::Result<()> {
+        let json = serde_json::to_vec_pretty(self).map_err(io::Error::other)?;
+        fs::write(output_dir.join(STATE_FILE_NAME), json)
+    }
+}
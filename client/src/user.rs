@@ -0,0 +1,77 @@
+use notion_to_markdown_core::User;
+
+#[derive(serde::Deserialize)]
+pub(crate) struct ApiUser {
+    id: String,
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    person: Option<ApiPerson>,
+}
+
+#[derive(serde::Deserialize)]
+struct ApiPerson {
+    #[serde(default)]
+    email: Option<String>,
+}
+
+#[derive(serde::Deserialize)]
+pub(crate) struct ApiUserList {
+    pub(crate) results: Vec<ApiUser>,
+    pub(crate) next_cursor: Option<String>,
+}
+
+impl From<ApiUser> for User {
+    fn from(user: ApiUser) -> Self {
+        User {
+            id: user.id,
+            name: user.name,
+            email: user.person.and_then(|person| person.email),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use notion_to_markdown_core::User;
+    use pretty_assertions::assert_eq;
+
+    use super::ApiUser;
+
+    #[test]
+    fn test_from_api_user() {
+        let person: ApiUser = serde_json::from_value(serde_json::json!({
+            "object": "user",
+            "id": "person-id",
+            "type": "person",
+            "name": "Ada Lovelace",
+            "avatar_url": null,
+            "person": { "email": "ada@example.com" }
+        }))
+        .unwrap();
+        let bot: ApiUser = serde_json::from_value(serde_json::json!({
+            "object": "user",
+            "id": "bot-id",
+            "type": "bot",
+            "bot": {}
+        }))
+        .unwrap();
+
+        assert_eq!(
+            User::from(person),
+            User {
+                id: "person-id".into(),
+                name: Some("Ada Lovelace".into()),
+                email: Some("ada@example.com".into()),
+            }
+        );
+        assert_eq!(
+            User::from(bot),
+            User {
+                id: "bot-id".into(),
+                name: None,
+                email: None,
+            }
+        );
+    }
+}
@@ -0,0 +1,429 @@
+mod support;
+
+use client::{NotionClient, NotionClientError, NotionErrorCode, WarningKind};
+use indoc::indoc;
+use notion_to_markdown_core::{BlockChildren, MarkdownRenderOptions, markdown_to_blocks};
+use pretty_assertions::assert_eq;
+use support::MockNotionServer;
+
+fn client_for(server: &MockNotionServer) -> NotionClient {
+    NotionClient::builder("secret_token".into())
+        .base_url(server.base_url())
+        .build()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_pagination() {
+    let server = MockNotionServer::start("workspace");
+
+    let blocks = client_for(&server)
+        .retrieve_block_children("paginated-page", None, Some(1))
+        .await
+        .unwrap();
+
+    assert_eq!(
+        blocks.to_markdown(0),
+        indoc! {r#"
+            first page
+
+            second page
+
+            third page
+        "#}
+    );
+
+    let requests = server.requests_to("/v1/blocks/paginated-page/children");
+    let cursors: Vec<Option<&str>> = requests
+        .iter()
+        .map(|request| request.query.get("start_cursor").map(String::as_str))
+        .collect();
+    assert_eq!(cursors, vec![None, Some("cursor-2"), Some("cursor-3")]);
+    assert!(
+        requests
+            .iter()
+            .all(|request| request.query.get("page_size").map(String::as_str) == Some("1"))
+    );
+}
+
+#[tokio::test]
+async fn test_request_headers() {
+    let server = MockNotionServer::start("workspace");
+
+    NotionClient::builder("secret_token".into())
+        .base_url(server.base_url())
+        .version("2025-09-03")
+        .user_agent("notion-to-markdown-test")
+        .build()
+        .unwrap()
+        .retrieve_block_children("paginated-page", None, None)
+        .await
+        .unwrap();
+
+    let request = &server.requests()[0];
+    assert_eq!(request.method, "GET");
+    assert_eq!(request.headers["authorization"], "Bearer secret_token");
+    assert_eq!(request.headers["notion-version"], "2025-09-03");
+    assert_eq!(request.headers["user-agent"], "notion-to-markdown-test");
+}
+
+#[tokio::test]
+async fn test_retry_when_rate_limited() {
+    let server = MockNotionServer::start("workspace");
+    server.rate_limit("/v1/blocks/paginated-page/children", 2);
+
+    let blocks = client_for(&server)
+        .retrieve_block_children("paginated-page", None, None)
+        .await
+        .unwrap();
+
+    assert_eq!(blocks.len(), 3);
+    assert_eq!(
+        server
+            .requests_to("/v1/blocks/paginated-page/children")
+            .len(),
+        5
+    );
+}
+
+#[tokio::test]
+async fn test_give_up_when_rate_limited_too_often() {
+    let server = MockNotionServer::start("workspace");
+    server.rate_limit("/v1/blocks/paginated-page/children", 10);
+    let client = NotionClient::builder("secret_token".into())
+        .base_url(server.base_url())
+        .max_retries(2)
+        .build()
+        .unwrap();
+
+    let err = client
+        .retrieve_block_children("paginated-page", None, None)
+        .await
+        .unwrap_err();
+
+    assert!(matches!(err, NotionClientError::Api(_)));
+    assert!(err.is_rate_limited());
+    assert_eq!(
+        server
+            .requests_to("/v1/blocks/paginated-page/children")
+            .len(),
+        3
+    );
+}
+
+#[tokio::test]
+async fn test_recursion() {
+    let server = MockNotionServer::start("workspace");
+
+    let blocks = client_for(&server)
+        .retrieve_block_children("nested-page", None, None)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        blocks.to_markdown(0),
+        indoc! {r#"
+            outer toggle
+
+            - inside toggle
+              - nested item
+
+            after toggle
+        "#}
+    );
+
+    let paths: Vec<String> = server
+        .requests()
+        .into_iter()
+        .map(|request| request.path)
+        .collect();
+    assert_eq!(
+        paths,
+        vec![
+            "/v1/blocks/nested-page/children",
+            "/v1/blocks/toggle-1/children",
+            "/v1/blocks/bulleted-1/children",
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_unparsed_block_does_not_abort_export() {
+    let server = MockNotionServer::start("workspace");
+
+    let (blocks, report) = client_for(&server)
+        .retrieve_block_children_with_report("broken-page", None, None)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        blocks.to_markdown(0),
+        indoc! {r#"
+            before
+
+            <!-- unparsed image block: image-1 -->
+
+            <!-- unparsed toggle block: broken-toggle -->
+
+            inside broken toggle
+
+            after
+        "#}
+    );
+    let ids: Vec<Option<&str>> = report
+        .warnings
+        .iter()
+        .map(|w| w.block_id.as_deref())
+        .collect();
+    assert_eq!(ids, vec![Some("image-1"), Some("broken-toggle")]);
+}
+
+#[tokio::test]
+async fn test_api_errors() {
+    let server = MockNotionServer::start("workspace");
+    let client = client_for(&server);
+
+    let not_found = client
+        .retrieve_block_children("missing-page", None, None)
+        .await
+        .unwrap_err();
+    let restricted = client
+        .retrieve_block_children("restricted-page", None, None)
+        .await
+        .unwrap_err();
+
+    match not_found {
+        NotionClientError::Api(e) => {
+            assert_eq!(e.status, reqwest::StatusCode::NOT_FOUND);
+            assert_eq!(e.code, NotionErrorCode::ObjectNotFound);
+            assert_eq!(e.request_id.as_deref(), Some("request"));
+        }
+        e => panic!("unexpected error: {:?}", e),
+    }
+    match restricted {
+        NotionClientError::Api(e) => assert_eq!(e.code, NotionErrorCode::RestrictedResource),
+        e => panic!("unexpected error: {:?}", e),
+    }
+}
+
+#[tokio::test]
+async fn test_download_file() {
+    let server = MockNotionServer::start("workspace");
+    let client = client_for(&server);
+
+    let bytes = client
+        .download_file(&format!("{}/assets/image.png", server.base_url()))
+        .await
+        .unwrap();
+    let missing = client
+        .download_file(&format!("{}/assets/missing.png", server.base_url()))
+        .await
+        .unwrap_err();
+
+    assert_eq!(bytes, b"\x89PNG\r\n\x1a\n");
+    assert!(matches!(
+        missing,
+        NotionClientError::Status(reqwest::StatusCode::NOT_FOUND)
+    ));
+    assert!(
+        !server.requests_to("/assets/image.png")[0]
+            .headers
+            .contains_key("authorization")
+    );
+}
+
+#[tokio::test]
+async fn test_synced_block_copies() {
+    let server = MockNotionServer::start("workspace");
+
+    let (blocks, report) = client_for(&server)
+        .retrieve_block_children_with_report("synced-page", None, None)
+        .await
+        .unwrap();
+
+    let options = MarkdownRenderOptions {
+        synced_block_markers: true,
+        ..Default::default()
+    };
+    assert_eq!(
+        blocks.to_markdown_with_options(0, &options),
+        indoc! {r#"
+            <!-- synced block: synced-original -->
+            shared content
+            <!-- /synced block: synced-original -->
+
+            <!-- synced block: synced-original -->
+            shared content
+            <!-- /synced block: synced-original -->
+
+            <!-- synced block: synced-original -->
+            shared content
+            <!-- /synced block: synced-original -->
+
+            <!-- synced block: synced-hidden -->
+            <!-- /synced block: synced-hidden -->
+        "#}
+    );
+    assert_eq!(
+        server
+            .requests_to("/v1/blocks/synced-original/children")
+            .len(),
+        1
+    );
+    assert_eq!(report.warnings.len(), 1);
+    assert_eq!(
+        report.warnings[0].block_id.as_deref(),
+        Some("synced-copy-3")
+    );
+}
+
+#[tokio::test]
+async fn test_link_to_page_titles() {
+    let server = MockNotionServer::start("workspace");
+    let client = client_for(&server);
+
+    let (blocks, report) = client
+        .retrieve_block_children_with_report("linked-page", None, None)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        blocks.to_markdown(0),
+        indoc! {r#"
+            [Child Page](https://www.notion.so/export-child)
+
+            [Task List](https://www.notion.so/linked-database)
+
+            [Restricted page](https://www.notion.so/restricted-target)
+
+            [Child Page](https://www.notion.so/export-child)
+        "#}
+    );
+    assert_eq!(server.requests_to("/v1/pages/export-child").len(), 1);
+    assert_eq!(report.warnings.len(), 1);
+    assert_eq!(report.warnings[0].block_id.as_deref(), Some("link-3"));
+}
+
+#[tokio::test]
+async fn test_database_link_titles() {
+    let server = MockNotionServer::start("workspace");
+
+    let (blocks, report) = client_for(&server)
+        .retrieve_block_children_with_report("database-links", None, None)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        blocks.to_markdown(0),
+        indoc! {r#"
+            [Task List](https://www.notion.so/linked-database)
+
+            [Restricted page](https://www.notion.so/restricted-database)
+
+            [Task List](https://www.notion.so/linked-database)
+        "#}
+    );
+    assert_eq!(server.requests_to("/v1/databases/linked-database").len(), 1);
+    assert_eq!(
+        server
+            .requests_to("/v1/databases/restricted-database")
+            .len(),
+        1
+    );
+    assert_eq!(report.warnings.len(), 1);
+    assert_eq!(report.warnings[0].kind, WarningKind::ApiError);
+    assert_eq!(
+        report.warnings[0].block_id.as_deref(),
+        Some("database-link-2")
+    );
+}
+
+#[tokio::test]
+async fn test_append_block_children() {
+    let server = MockNotionServer::start("workspace");
+
+    let blocks = markdown_to_blocks(indoc! {r#"
+        # this is headline
+
+        - this is list
+          - this is nested
+            - this is deep
+
+        | this | is |
+        | ---- | -- |
+        | a    | b  |
+    "#});
+    let ids = client_for(&server)
+        .append_block_children("import-page", blocks)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        ids,
+        vec!["imported-heading", "imported-list", "imported-table"]
+    );
+
+    let paths: Vec<(String, String)> = server
+        .requests()
+        .into_iter()
+        .map(|request| (request.method, request.path))
+        .collect();
+    assert_eq!(
+        paths,
+        vec![
+            ("PATCH".into(), "/v1/blocks/import-page/children".into()),
+            ("PATCH".into(), "/v1/blocks/imported-list/children".into()),
+            ("PATCH".into(), "/v1/blocks/imported-nested/children".into()),
+        ]
+    );
+
+    let requests = server.requests();
+    let body: serde_json::Value = serde_json::from_str(&requests[0].body).unwrap();
+    assert!(body["children"][1]["bulleted_list_item"]["children"].is_null());
+    assert_eq!(
+        body["children"][2]["table"]["children"]
+            .as_array()
+            .unwrap()
+            .len(),
+        2
+    );
+
+    let body: serde_json::Value = serde_json::from_str(&requests[2].body).unwrap();
+    assert_eq!(
+        body["children"][0]["bulleted_list_item"]["rich_text"][0]["text"]["content"],
+        "this is deep"
+    );
+}
+
+#[tokio::test]
+async fn test_users_are_cached() {
+    let server = MockNotionServer::start("workspace");
+    let client = client_for(&server);
+
+    let users = client.list_users().await.unwrap();
+    let names: Vec<Option<&str>> = users.iter().map(|user| user.name.as_deref()).collect();
+    assert_eq!(
+        names,
+        vec![
+            Some("Ada Lovelace"),
+            Some("Export Bot"),
+            Some("Grace Hopper")
+        ]
+    );
+    assert_eq!(users[0].email.as_deref(), Some("ada@example.com"));
+    assert_eq!(users[1].email, None);
+    assert_eq!(client.list_users().await.unwrap(), users);
+    assert_eq!(server.requests_to("/v1/users").len(), 2);
+
+    assert_eq!(client.retrieve_user("user-ada").await.unwrap(), users[0]);
+    let guest = client.retrieve_user("user-guest").await.unwrap();
+    assert_eq!(guest.email.as_deref(), Some("guest@example.com"));
+    client.retrieve_user("user-guest").await.unwrap();
+    assert_eq!(server.requests_to("/v1/users/user-ada").len(), 0);
+    assert_eq!(server.requests_to("/v1/users/user-guest").len(), 1);
+
+    match client.retrieve_user("user-removed").await.unwrap_err() {
+        NotionClientError::Api(e) => assert_eq!(e.code, NotionErrorCode::ObjectNotFound),
+        e => panic!("unexpected error: {:?}", e),
+    }
+}
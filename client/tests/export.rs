@@ -0,0 +1,592 @@
+mod support;
+
+use std::{fs, path::PathBuf, sync::Arc};
+
+use client::{
+    DiagramCommand, ExportError, ExportProfile, Exporter, Manifest, NotionClient, PageStatus,
+    WarningKind,
+};
+use indoc::indoc;
+use notion_to_markdown_core::{
+    ColumnLayout, MarkdownRenderOptions, NamingStrategy, Snapshot, UserDirectory,
+};
+use pretty_assertions::assert_eq;
+use support::MockNotionServer;
+
+fn client_for(server: &MockNotionServer) -> NotionClient {
+    NotionClient::builder("secret_token".into())
+        .base_url(server.base_url())
+        .build()
+        .unwrap()
+}
+
+fn children_requests(server: &MockNotionServer) -> usize {
+    server
+        .requests()
+        .iter()
+        .filter(|request| request.path.ends_with("/children"))
+        .count()
+}
+
+#[tokio::test]
+async fn test_export_writes_nested_pages() {
+    let server = MockNotionServer::start("workspace");
+    let client = client_for(&server);
+    let dir = tempfile::tempdir().unwrap();
+
+    let report = Exporter::new(&client, dir.path())
+        .export("export-root")
+        .await
+        .unwrap();
+
+    assert_eq!(
+        report.written,
+        vec![
+            PathBuf::from("Export_Root.md"),
+            PathBuf::from("Child_Page.md")
+        ]
+    );
+    assert_eq!(
+        fs::read_to_string(dir.path().join("Export_Root.md")).unwrap(),
+        indoc! {r#"
+            this is root page
+
+            [Child Page: Child Page](Child_Page.md)
+
+            more pages
+
+            [Child Page: Archived Page](https://www.notion.so/export-archived)
+        "#}
+    );
+    assert_eq!(
+        fs::read_to_string(dir.path().join("Child_Page.md")).unwrap(),
+        "this is child page\n"
+    );
+    assert!(!dir.path().join("Archived_Page.md").exists());
+    assert!(dir.path().join(".notion-to-markdown.json").exists());
+}
+
+#[tokio::test]
+async fn test_export_skips_unchanged_pages() {
+    let server = MockNotionServer::start("workspace");
+    let client = client_for(&server);
+    let dir = tempfile::tempdir().unwrap();
+
+    Exporter::new(&client, dir.path())
+        .export("export-root")
+        .await
+        .unwrap();
+    let first_run = children_requests(&server);
+
+    let report = Exporter::new(&client, dir.path())
+        .export("export-root")
+        .await
+        .unwrap();
+
+    assert!(report.written.is_empty());
+    assert_eq!(
+        report.unchanged,
+        vec![
+            PathBuf::from("Export_Root.md"),
+            PathBuf::from("Child_Page.md")
+        ]
+    );
+    assert_eq!(children_requests(&server), first_run);
+}
+
+#[tokio::test]
+async fn test_export_refreshes_changed_and_removes_stale_pages() {
+    let server = MockNotionServer::start("workspace");
+    let client = client_for(&server);
+    let dir = tempfile::tempdir().unwrap();
+
+    Exporter::new(&client, dir.path())
+        .export("export-root")
+        .await
+        .unwrap();
+
+    let state_path = dir.path().join(".notion-to-markdown.json");
+    let mut state: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&state_path).unwrap()).unwrap();
+    state["pages"]["export-child"]["last_edited_time"] = "1999-01-01T00:00:00.000Z".into();
+    state["pages"]["deleted-page"] = serde_json::json!({
+        "title": "Deleted Page",
+        "path": "Deleted_Page.md",
+        "last_edited_time": "1999-01-01T00:00:00.000Z",
+        "child_pages": [],
+        "blocks": {}
+    });
+    fs::write(&state_path, state.to_string()).unwrap();
+    fs::write(dir.path().join("Deleted_Page.md"), "deleted").unwrap();
+
+    let report = Exporter::new(&client, dir.path())
+        .export("export-root")
+        .await
+        .unwrap();
+
+    // The root page didn't link to the page that disappeared, so it is kept as it was.
+    assert_eq!(report.written, vec![PathBuf::from("Child_Page.md")]);
+    assert_eq!(report.unchanged, vec![PathBuf::from("Export_Root.md")]);
+    assert_eq!(report.removed, vec![PathBuf::from("Deleted_Page.md")]);
+    assert!(!dir.path().join("Deleted_Page.md").exists());
+}
+
+#[tokio::test]
+async fn test_export_rewrites_pages_linking_to_moved_pages() {
+    let server = MockNotionServer::start("workspace");
+    let client = client_for(&server);
+    let dir = tempfile::tempdir().unwrap();
+
+    Exporter::new(&client, dir.path())
+        .export("export-root")
+        .await
+        .unwrap();
+
+    let state_path = dir.path().join(".notion-to-markdown.json");
+    let mut state: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&state_path).unwrap()).unwrap();
+    state["pages"]["export-child"]["path"] = "Old_Child.md".into();
+    fs::write(&state_path, state.to_string()).unwrap();
+    fs::rename(
+        dir.path().join("Child_Page.md"),
+        dir.path().join("Old_Child.md"),
+    )
+    .unwrap();
+    let requests = server.requests_to("/v1/blocks/export-root/children").len();
+
+    let report = Exporter::new(&client, dir.path())
+        .export("export-root")
+        .await
+        .unwrap();
+
+    assert_eq!(
+        report.written,
+        vec![
+            PathBuf::from("Export_Root.md"),
+            PathBuf::from("Child_Page.md")
+        ]
+    );
+    assert_eq!(report.removed, vec![PathBuf::from("Old_Child.md")]);
+    assert_eq!(
+        server.requests_to("/v1/blocks/export-root/children").len(),
+        requests + 1
+    );
+}
+
+#[tokio::test]
+async fn test_full_export_ignores_state() {
+    let server = MockNotionServer::start("workspace");
+    let client = client_for(&server);
+    let dir = tempfile::tempdir().unwrap();
+
+    Exporter::new(&client, dir.path())
+        .export("export-root")
+        .await
+        .unwrap();
+    let report = Exporter::new(&client, dir.path())
+        .incremental(false)
+        .export("export-root")
+        .await
+        .unwrap();
+
+    assert_eq!(report.written.len(), 2);
+    assert!(report.unchanged.is_empty());
+}
+
+#[tokio::test]
+async fn test_snapshot_renders_offline() {
+    let server = MockNotionServer::start("workspace");
+    let client = client_for(&server);
+    let dir = tempfile::tempdir().unwrap();
+
+    Exporter::new(&client, dir.path())
+        .snapshots(true)
+        .export("export-root")
+        .await
+        .unwrap();
+
+    let snapshot: Snapshot = serde_json::from_str(
+        &fs::read_to_string(dir.path().join("Export_Root.snapshot.json")).unwrap(),
+    )
+    .unwrap();
+    drop(server);
+
+    assert_eq!(snapshot.page.title, "Export Root");
+    assert!(
+        snapshot
+            .blocks
+            .iter()
+            .all(|block| block.id().is_some_and(|id| !id.is_empty()))
+    );
+    assert_eq!(
+        snapshot.to_markdown_with_options(&MarkdownRenderOptions::default()),
+        indoc! {r#"
+            this is root page
+
+            [Child Page: Child Page](https://www.notion.so/export-child)
+
+            more pages
+
+            [Child Page: Archived Page](https://www.notion.so/export-archived)
+        "#}
+    );
+}
+
+#[tokio::test]
+async fn test_diagrams_render_to_svg() {
+    let server = MockNotionServer::start("workspace");
+    let client = client_for(&server);
+    let dir = tempfile::tempdir().unwrap();
+
+    let report = Exporter::new(&client, dir.path())
+        .diagram_command(DiagramCommand::new(
+            "sh",
+            ["-c", "cp \"$0\" \"$1\"", "{input}", "{output}"],
+        ))
+        .export("diagram-page")
+        .await
+        .unwrap();
+
+    assert!(report.warnings.is_empty());
+    assert_eq!(
+        fs::read_to_string(dir.path().join("Diagrams.md")).unwrap(),
+        indoc! {r#"
+            ![diagram](Diagrams-diagram-1.svg)
+
+            ```rust
+            fn main() {}
+            ```
+        "#}
+    );
+    assert_eq!(
+        fs::read_to_string(dir.path().join("Diagrams-diagram-1.svg")).unwrap(),
+        "graph TD\n  A --> B"
+    );
+    assert!(!dir.path().join("Diagrams-diagram-1.mmd").exists());
+}
+
+#[tokio::test]
+async fn test_diagrams_fall_back_to_code_without_command() {
+    let server = MockNotionServer::start("workspace");
+    let client = client_for(&server);
+    let dir = tempfile::tempdir().unwrap();
+
+    let report = Exporter::new(&client, dir.path())
+        .diagram_command(DiagramCommand::new(
+            "notion-to-markdown-missing-command",
+            ["{input}", "{output}"],
+        ))
+        .export("diagram-page")
+        .await
+        .unwrap();
+
+    assert_eq!(report.warnings.len(), 1);
+    assert_eq!(report.warnings[0].block_id.as_deref(), Some("diagram-1"));
+    assert_eq!(
+        fs::read_to_string(dir.path().join("Diagrams.md")).unwrap(),
+        indoc! {r#"
+            ```mermaid
+            graph TD
+              A --> B
+            ```
+
+            ```rust
+            fn main() {}
+            ```
+        "#}
+    );
+}
+
+#[tokio::test]
+async fn test_table_layout_fallback_is_reported() {
+    let server = MockNotionServer::start("workspace");
+    let client = client_for(&server);
+    let dir = tempfile::tempdir().unwrap();
+
+    let report = Exporter::new(&client, dir.path())
+        .options(MarkdownRenderOptions {
+            column_layout: ColumnLayout::Table,
+            ..Default::default()
+        })
+        .export("columns-page")
+        .await
+        .unwrap();
+
+    assert_eq!(report.count(WarningKind::Layout), 1);
+    assert_eq!(report.warnings[0].block_id.as_deref(), Some("columns-1"));
+    assert_eq!(
+        fs::read_to_string(dir.path().join("Columns.md")).unwrap(),
+        indoc! {r#"
+            left
+
+            -----
+        "#}
+    );
+}
+
+#[tokio::test]
+async fn test_mkdocs_profile_layout() {
+    let server = MockNotionServer::start("workspace");
+    let client = client_for(&server);
+    let dir = tempfile::tempdir().unwrap();
+
+    let report = Exporter::new(&client, dir.path())
+        .profile(ExportProfile::MkDocs)
+        .export("export-root")
+        .await
+        .unwrap();
+
+    assert_eq!(
+        report.written,
+        vec![
+            PathBuf::from("docs/index.md"),
+            PathBuf::from("docs/Child_Page.md")
+        ]
+    );
+    assert_eq!(
+        fs::read_to_string(dir.path().join("docs/index.md")).unwrap(),
+        indoc! {r#"
+            ---
+            title: "Export Root"
+            ---
+
+            this is root page
+
+            [Child Page: Child Page](Child_Page.md)
+
+            more pages
+
+            [Child Page: Archived Page](https://www.notion.so/export-archived)
+        "#}
+    );
+    assert_eq!(
+        fs::read_to_string(dir.path().join("mkdocs.yml")).unwrap(),
+        indoc! {r#"
+            site_name: "Export Root"
+            theme:
+              name: material
+              features:
+                - navigation.indexes
+            markdown_extensions:
+              - admonition
+              - pymdownx.superfences:
+                  custom_fences:
+                    - name: mermaid
+                      class: mermaid
+                      format: !!python/name:pymdownx.superfences.fence_code_format
+              - pymdownx.arithmatex:
+                  generic: true
+            extra_javascript:
+              - https://unpkg.com/mathjax@3/es5/tex-mml-chtml.js
+            nav:
+              - "Export Root": "index.md"
+              - "Child Page": "Child_Page.md"
+        "#}
+    );
+}
+
+#[tokio::test]
+async fn test_mdbook_profile_writes_buildable_book() {
+    let server = MockNotionServer::start("workspace");
+    let client = client_for(&server);
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("book.toml"), "[book]\ntitle = \"Mine\"\n").unwrap();
+
+    Exporter::new(&client, dir.path())
+        .profile(ExportProfile::MdBook)
+        .export("export-root")
+        .await
+        .unwrap();
+
+    assert_eq!(
+        fs::read_to_string(dir.path().join("src/SUMMARY.md")).unwrap(),
+        indoc! {r#"
+            # Summary
+
+            [Export Root](README.md)
+
+            - [Child Page](Child_Page.md)
+        "#}
+    );
+    assert!(dir.path().join("src/README.md").exists());
+    assert!(dir.path().join("src/Child_Page.md").exists());
+    assert_eq!(
+        fs::read_to_string(dir.path().join("book.toml")).unwrap(),
+        "[book]\ntitle = \"Mine\"\n"
+    );
+}
+
+#[tokio::test]
+async fn test_colliding_titles_get_unique_names() {
+    let server = MockNotionServer::start("workspace");
+    let client = client_for(&server);
+    let dir = tempfile::tempdir().unwrap();
+
+    let report = Exporter::new(&client, dir.path())
+        .export("collision-root")
+        .await
+        .unwrap();
+
+    assert_eq!(
+        report.written,
+        vec![
+            PathBuf::from("Notes__2024_Q1.md"),
+            PathBuf::from("Meeting_notes.md"),
+            PathBuf::from("Meeting_notes-2.md")
+        ]
+    );
+    assert_eq!(
+        fs::read_to_string(dir.path().join("Notes__2024_Q1.md")).unwrap(),
+        indoc! {r#"
+            [Child Page: Meeting notes](Meeting_notes.md)
+
+            [Child Page: Meeting notes](Meeting_notes-2.md)
+        "#}
+    );
+}
+
+#[tokio::test]
+async fn test_slug_naming() {
+    let server = MockNotionServer::start("workspace");
+    let client = client_for(&server);
+    let dir = tempfile::tempdir().unwrap();
+
+    let report = Exporter::new(&client, dir.path())
+        .naming(NamingStrategy::Slug)
+        .export("collision-root")
+        .await
+        .unwrap();
+
+    assert_eq!(
+        report.written,
+        vec![
+            PathBuf::from("notes-2024-q1.md"),
+            PathBuf::from("meeting-notes.md"),
+            PathBuf::from("meeting-notes-2.md")
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_manifest_lists_pages_and_skipped_blocks() {
+    let server = MockNotionServer::start("workspace");
+    let client = client_for(&server);
+    let dir = tempfile::tempdir().unwrap();
+
+    let report = Exporter::new(&client, dir.path())
+        .export("report-page")
+        .await
+        .unwrap();
+
+    let kinds: Vec<WarningKind> = report.warnings.iter().map(|w| w.kind).collect();
+    assert_eq!(
+        kinds,
+        vec![
+            WarningKind::UnsupportedBlock,
+            WarningKind::UnsupportedBlock,
+            WarningKind::UnparsedBlock
+        ]
+    );
+    assert_eq!(
+        report.to_string(),
+        "1 page written, 0 unchanged, 0 removed\n\
+         3 warnings: 1 unparsed block, 2 unsupported blocks"
+    );
+
+    let manifest = Manifest::load(dir.path()).unwrap();
+    assert_eq!(manifest.root_page_id, "report-page");
+    assert_eq!(manifest.pages.len(), 1);
+    let page = &manifest.pages[0];
+    assert_eq!(
+        (page.id.as_str(), page.title.as_str(), page.path.as_str()),
+        ("report-page", "Report Page", "Report_Page.md")
+    );
+    assert_eq!(page.status, PageStatus::Written);
+    assert_eq!(page.warnings, report.warnings);
+    let block_types: Vec<Option<&str>> = page
+        .warnings
+        .iter()
+        .map(|w| w.block_type.as_deref())
+        .collect();
+    assert_eq!(
+        block_types,
+        vec![Some("unsupported"), Some("form"), Some("image")]
+    );
+
+    // Unchanged pages keep their warnings in the manifest and the report.
+    let report = Exporter::new(&client, dir.path())
+        .export("report-page")
+        .await
+        .unwrap();
+    let manifest = Manifest::load(dir.path()).unwrap();
+    assert_eq!(manifest.pages[0].status, PageStatus::Unchanged);
+    assert_eq!(manifest.pages[0].warnings.len(), 3);
+    assert_eq!(report.warnings.len(), 3);
+}
+
+#[tokio::test]
+async fn test_max_warnings_fails_export() {
+    let server = MockNotionServer::start("workspace");
+    let client = client_for(&server);
+    let dir = tempfile::tempdir().unwrap();
+
+    let err = Exporter::new(&client, dir.path())
+        .max_warnings(2)
+        .export("report-page")
+        .await
+        .unwrap_err();
+
+    let ExportError::TooManyWarnings { max, report } = err else {
+        panic!("expected TooManyWarnings, got {:?}", err);
+    };
+    assert_eq!(max, 2);
+    assert_eq!(report.written, vec![PathBuf::from("Report_Page.md")]);
+    assert!(dir.path().join("manifest.json").exists());
+
+    Exporter::new(&client, dir.path())
+        .max_warnings(3)
+        .export("report-page")
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_user_mentions() {
+    let server = MockNotionServer::start("workspace");
+    let client = client_for(&server);
+    let dir = tempfile::tempdir().unwrap();
+
+    Exporter::new(&client, dir.path())
+        .export("mention-page")
+        .await
+        .unwrap();
+    assert_eq!(
+        fs::read_to_string(dir.path().join("Mention_Page.md")).unwrap(),
+        "reviewed by Ada, Unknown user, Guest User and Unknown user\n"
+    );
+    assert!(server.requests_to("/v1/users").is_empty());
+
+    let mut users = UserDirectory::new();
+    users.insert_handle("ada@example.com", "ada");
+    users.insert_handle("guest@example.com", "guest");
+    Exporter::new(&client, dir.path())
+        .options(MarkdownRenderOptions {
+            users: Some(Arc::new(users)),
+            user_mention_template: "[@{handle}](https://github.com/{handle})".into(),
+            ..Default::default()
+        })
+        .resolve_users(true)
+        .incremental(false)
+        .export("mention-page")
+        .await
+        .unwrap();
+    assert_eq!(
+        fs::read_to_string(dir.path().join("Mention_Page.md")).unwrap(),
+        "reviewed by [@ada](https://github.com/ada), Export Bot, \
+            [@guest](https://github.com/guest) and Unknown user\n"
+    );
+    // Guests aren't listed, and removed users can't be retrieved either.
+    assert_eq!(server.requests_to("/v1/users/user-guest").len(), 1);
+    assert_eq!(server.requests_to("/v1/users/user-removed").len(), 1);
+    assert!(server.requests_to("/v1/users/user-ada").is_empty());
+}
@@ -0,0 +1,240 @@
+//! Local stand-in for the Notion API that serves recorded JSON responses from a directory.
+//!
+//! A request for `/v1/pages/{id}` or `/v1/databases/{id}` is answered with
+//! `{dir}/v1/pages/{id}.json` or `{dir}/v1/databases/{id}.json`, and one for
+//! `/v1/blocks/{id}/children` with `{dir}/v1/blocks/{id}/children.json`,
+//! and with `{dir}/v1/blocks/{id}/children.{cursor}.json` when `start_cursor` is set. Requests
+//! with another method than GET read `{path}.{method}.json`, e.g. `children.patch.json`. Files that
+//! contain a Notion error object (`"object": "error"`) are served with the status they declare.
+//! Any other path is served verbatim from `{dir}` when the file exists, which covers assets.
+
+// Shared by several test crates, each of which uses only part of it.
+#![allow(dead_code)]
+
+use std::{
+    collections::HashMap,
+    fs,
+    io::{BufRead, BufReader, Read, Write},
+    net::{TcpListener, TcpStream},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    thread,
+};
+
+#[derive(Debug, Clone)]
+pub struct RecordedRequest {
+    pub method: String,
+    pub path: String,
+    pub query: HashMap<String, String>,
+    pub headers: HashMap<String, String>,
+    pub body: String,
+}
+
+#[derive(Default)]
+struct State {
+    requests: Vec<RecordedRequest>,
+    rate_limits: HashMap<String, usize>,
+}
+
+pub struct MockNotionServer {
+    base_url: String,
+    state: Arc<Mutex<State>>,
+}
+
+impl MockNotionServer {
+    pub fn start(fixture_dir: impl AsRef<Path>) -> MockNotionServer {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let state = Arc::new(Mutex::new(State::default()));
+        let root = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures")
+            .join(fixture_dir);
+
+        let server_state = state.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(stream) = stream else { break };
+                handle(stream, &root, &server_state);
+            }
+        });
+
+        MockNotionServer { base_url, state }
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// Answers the next `times` requests for `path` with `429 Too Many Requests` and a
+    /// `Retry-After` of zero seconds.
+    pub fn rate_limit(&self, path: &str, times: usize) {
+        self.state
+            .lock()
+            .unwrap()
+            .rate_limits
+            .insert(path.into(), times);
+    }
+
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        self.state.lock().unwrap().requests.clone()
+    }
+
+    pub fn requests_to(&self, path: &str) -> Vec<RecordedRequest> {
+        self.requests()
+            .into_iter()
+            .filter(|request| request.path == path)
+            .collect()
+    }
+}
+
+fn handle(mut stream: TcpStream, root: &Path, state: &Mutex<State>) {
+    let Some(request) = read_request(&stream) else {
+        return;
+    };
+
+    let rate_limited = {
+        let mut state = state.lock().unwrap();
+        state.requests.push(request.clone());
+        match state.rate_limits.get_mut(&request.path) {
+            Some(remaining) if *remaining > 0 => {
+                *remaining -= 1;
+                true
+            }
+            _ => false,
+        }
+    };
+
+    let retry_after = if rate_limited {
+        "Retry-After: 0\r\n"
+    } else {
+        ""
+    };
+    let (status, content_type, body) = if rate_limited {
+        (
+            429,
+            "application/json",
+            br#"{"object":"error","status":429,"code":"rate_limited","message":"You have been rate limited."}"#
+                .to_vec(),
+        )
+    } else {
+        respond(&request, root)
+    };
+
+    let head = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\n{}Connection: close\r\n\r\n",
+        status,
+        reason(status),
+        content_type,
+        body.len(),
+        retry_after
+    );
+    let _ = stream.write_all(head.as_bytes());
+    let _ = stream.write_all(&body);
+}
+
+fn respond(request: &RecordedRequest, root: &Path) -> (u16, &'static str, Vec<u8>) {
+    let relative = request.path.trim_start_matches('/');
+
+    if relative.starts_with("v1/") {
+        let file = match request.query.get("start_cursor") {
+            _ if request.method != "GET" => {
+                format!("{}.{}.json", relative, request.method.to_ascii_lowercase())
+            }
+            Some(cursor) => format!("{}.{}.json", relative, cursor),
+            None => format!("{}.json", relative),
+        };
+
+        return match fs::read(root.join(file)) {
+            Ok(body) => (json_status(&body), "application/json", body),
+            Err(_) => not_found(&request.path),
+        };
+    }
+
+    let path: PathBuf = root.join(relative);
+    match fs::read(path) {
+        Ok(body) => (200, "application/octet-stream", body),
+        Err(_) => not_found(&request.path),
+    }
+}
+
+fn json_status(body: &[u8]) -> u16 {
+    serde_json::from_slice::<serde_json::Value>(body)
+        .ok()
+        .filter(|value| value["object"] == "error")
+        .and_then(|value| value["status"].as_u64())
+        .map(|status| status as u16)
+        .unwrap_or(200)
+}
+
+fn not_found(path: &str) -> (u16, &'static str, Vec<u8>) {
+    let body = serde_json::json!({
+        "object": "error",
+        "status": 404,
+        "code": "object_not_found",
+        "message": format!("No fixture for {}", path),
+    });
+    (404, "application/json", body.to_string().into_bytes())
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        429 => "Too Many Requests",
+        _ => "Error",
+    }
+}
+
+fn read_request(stream: &TcpStream) -> Option<RecordedRequest> {
+    let mut reader = BufReader::new(stream);
+
+    let mut line = String::new();
+    reader.read_line(&mut line).ok()?;
+    let mut parts = line.split_whitespace();
+    let method = parts.next()?.to_string();
+    let target = parts.next()?.to_string();
+
+    let mut headers = HashMap::new();
+    loop {
+        let mut header = String::new();
+        reader.read_line(&mut header).ok()?;
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            headers.insert(name.trim().to_ascii_lowercase(), value.trim().to_string());
+        }
+    }
+
+    let length = headers
+        .get("content-length")
+        .and_then(|value| value.parse::<usize>().ok())
+        .unwrap_or(0);
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body).ok()?;
+
+    let (path, query) = match target.split_once('?') {
+        Some((path, query)) => (path.to_string(), parse_query(query)),
+        None => (target, HashMap::new()),
+    };
+
+    Some(RecordedRequest {
+        method,
+        path,
+        query,
+        headers,
+        body: String::from_utf8_lossy(&body).into_owned(),
+    })
+}
+
+fn parse_query(query: &str) -> HashMap<String, String> {
+    query
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect()
+}
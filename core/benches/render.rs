@@ -0,0 +1,104 @@
+use std::io;
+
+use criterion::{Criterion, Throughput, black_box, criterion_group, criterion_main};
+use notion_to_markdown_core::{Block, BlockChildren, MarkdownRenderOptions};
+use serde_json::json;
+
+const PAGE_BLOCKS: usize = 50_000;
+
+fn rich_text(content: &str) -> serde_json::Value {
+    json!([{
+        "type": "text",
+        "text": { "content": content, "link": null },
+        "annotations": {
+            "bold": false,
+            "italic": false,
+            "strikethrough": false,
+            "underline": false,
+            "code": false,
+            "color": "default"
+        },
+        "plain_text": content,
+        "href": null
+    }])
+}
+
+fn block(id: usize, block_type: &str) -> Block {
+    serde_json::from_value(json!({
+        "object": "block",
+        "id": format!("block-{}", id),
+        "type": block_type,
+        block_type: {
+            "rich_text": rich_text(&format!("{} number {}", block_type, id)),
+            "checked": id.is_multiple_of(2)
+        }
+    }))
+    .unwrap()
+}
+
+/// A page of `count` blocks mixing paragraphs, headings, toggles and lists nested four
+/// levels deep, the shapes that used to be rendered into nested strings.
+fn synthetic_page(count: usize) -> Vec<Block> {
+    let mut blocks = Vec::new();
+    let mut id = 0;
+
+    while id < count {
+        let mut heading = block(id, "heading_2");
+        id += 1;
+        for _ in 0..4 {
+            heading.append(block(id, "paragraph"));
+            id += 1;
+        }
+        blocks.push(heading);
+
+        let mut toggle = block(id, "toggle");
+        id += 1;
+        for _ in 0..5 {
+            let mut item = block(id, "bulleted_list_item");
+            id += 1;
+            let mut nested = block(id, "numbered_list_item");
+            id += 1;
+            let mut deeper = block(id, "to_do");
+            id += 1;
+            deeper.append(block(id, "bulleted_list_item"));
+            id += 1;
+            nested.append(deeper);
+            item.append(nested);
+            toggle.append(item);
+        }
+        blocks.push(toggle);
+    }
+
+    blocks
+}
+
+fn render(c: &mut Criterion) {
+    let blocks = synthetic_page(PAGE_BLOCKS);
+    let options = MarkdownRenderOptions::default();
+    let bytes = blocks.to_markdown_with_options(0, &options).len();
+
+    let mut group = c.benchmark_group("render_50k_blocks");
+    group.sample_size(20);
+    group.throughput(Throughput::Bytes(bytes as u64));
+    group.bench_function("to_markdown", |b| {
+        b.iter(|| black_box(blocks.to_markdown_with_options(0, &options)))
+    });
+    group.bench_function("write_markdown", |b| {
+        b.iter(|| {
+            let mut markdown = String::with_capacity(bytes);
+            blocks.write_markdown(&mut markdown, 0, &options).unwrap();
+            black_box(markdown)
+        })
+    });
+    group.bench_function("write_markdown_to", |b| {
+        b.iter(|| {
+            blocks
+                .write_markdown_to(&mut io::sink(), 0, &options)
+                .unwrap()
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, render);
+criterion_main!(benches);
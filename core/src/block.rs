@@ -1,5 +1,3 @@
-use core::panic;
-
 use bookmark::Bookmark;
 use breadcrumb::Breadcrumb;
 use bulleted_list_item::BulletedListItem;
@@ -23,14 +21,14 @@ use numbered_list_item::NumberedListItem;
 use paragraph::Paragraph;
 use pdf::Pdf;
 use quote::Quote;
-use serde;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use synced_block::SyncedBlock;
 use table::{Table, TableRow};
 use table_of_contents::TableOfContents;
 use template::Template;
 use to_do::ToDo;
 use toggle::Toggle;
+use unparsed::Unparsed;
 use video::Video;
 pub mod bookmark;
 pub mod breadcrumb;
@@ -61,16 +59,29 @@ pub mod table_of_contents;
 pub mod template;
 pub mod to_do;
 pub mod toggle;
+pub mod unparsed;
 pub mod video;
 
-use crate::rich_text::RichText;
+use std::{collections::HashMap, fmt, io, sync::Arc};
+
+use crate::{
+    error::RenderError,
+    page_registry::{
+        PageRegistry, encode_path, normalize_page_id, page_id_from_url, relative_path,
+    },
+    rich_text::{RichText, escape_markdown},
+    users::UserDirectory,
+};
 
 const UNSUPPORTED_NODE_TEXT: &str = "<!-- unsupported block -->";
 const UNEXPECTED_NODE_TEXT: &str = "<!-- unexpected block -->";
 const BREADCRUMB_NODE_TEXT: &str = "<!-- breadcrumb block -->";
 const TEMPLATE_NODE_TEXT: &str = "<!-- template block -->";
 const INDENT: &str = "  ";
-const NOTION_ORIGIN: &str = "https://www.notion.so";
+const DEFAULT_INACCESSIBLE_LINK_PLACEHOLDER: &str = "Restricted page";
+const DEFAULT_USER_MENTION_TEMPLATE: &str = "{name}";
+const DEFAULT_UNKNOWN_USER_PLACEHOLDER: &str = "Unknown user";
+pub(crate) const NOTION_ORIGIN: &str = "https://www.notion.so";
 
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
 pub enum ChildLinkTarget {
@@ -79,10 +90,111 @@ pub enum ChildLinkTarget {
     Notion,
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ColumnLayout {
+    /// Columns one after another, separated by blank lines.
+    #[default]
+    Sequential,
+    /// A flex `<div>` with one `<div>` per column, sized by the column width ratio.
+    HtmlFlex,
+    /// A single-row Markdown table with a cell per column. Markdown tables have no widths,
+    /// so the column width ratios are dropped. Column lists with anything but paragraphs in
+    /// a column can't be tables and are laid out sequentially; see [`ColumnList::fits_table`].
+    Table,
+}
+
+/// Syntax used for `code` blocks in the `mermaid` language, which Notion shows as diagrams.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MermaidTarget {
+    /// A code block fenced as `mermaid`, which GitHub renders as a diagram.
+    #[default]
+    GitHub,
+    /// A `mermaid` code block for the PyMdown SuperFences `mermaid` custom fence, which
+    /// Material for MkDocs renders as a diagram.
+    MkDocs,
+    /// A `mermaid` code block for `@docusaurus/theme-mermaid`.
+    Docusaurus,
+}
+
+/// Syntax callouts are written in.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CalloutStyle {
+    /// A plain blockquote starting with the callout icon.
+    #[default]
+    Blockquote,
+    /// A GitHub alert, `> [!NOTE]`, which Hugo renders as well.
+    GitHubAlert,
+    /// A Python-Markdown admonition, `!!! note`, as used by MkDocs.
+    MkDocs,
+    /// A Docusaurus admonition, `:::note`.
+    Docusaurus,
+}
+
+/// Delimiters around `equation` blocks.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MathDelimiters {
+    /// `$$ … $$`, as KaTeX, GitHub and most static site generators expect.
+    #[default]
+    Dollars,
+    /// `\[ … \]`.
+    Brackets,
+    /// `\\[ … \\]`, for MathJax in mdBook, whose Markdown parser eats one backslash.
+    EscapedBrackets,
+}
+
+/// Alignment of a table column, set by the colons of the separator row.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ColumnAlignment {
+    /// `---`, which most renderers show left-aligned.
+    #[default]
+    Default,
+    /// `:--`.
+    Left,
+    /// `:-:`.
+    Center,
+    /// `--:`.
+    Right,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct MarkdownRenderOptions {
     pub child_page_link_target: ChildLinkTarget,
     pub child_database_link_target: ChildLinkTarget,
+    /// Wrap the content of synced blocks in comments naming the original block.
+    pub synced_block_markers: bool,
+    pub column_layout: ColumnLayout,
+    /// Put an `<a id="{block id}"></a>` anchor in front of the text of headings, paragraphs,
+    /// list items, to-dos, toggles, quotes and callouts.
+    pub block_anchors: bool,
+    /// Pages written by the current export. Links to them resolve to their local path, links
+    /// to any other page to Notion.
+    pub page_registry: Option<Arc<PageRegistry>>,
+    /// Path of the page being rendered, relative to the export root, that local links are
+    /// made relative to.
+    pub current_page_path: Option<String>,
+    /// Link text of a `link_to_page` block whose target the integration cannot access.
+    pub inaccessible_link_placeholder: String,
+    pub mermaid_target: MermaidTarget,
+    pub callout_style: CalloutStyle,
+    pub math_delimiters: MathDelimiters,
+    /// Leave table cells unpadded, so editing one cell doesn't realign every row of the table
+    /// in a diff.
+    pub compact_tables: bool,
+    /// Right-align table columns holding only numbers, amounts of money or dates.
+    pub infer_table_alignment: bool,
+    /// Alignment of the columns of particular tables, by table block ID, first column first.
+    /// Columns past the end of the list are aligned as if the table had no entry.
+    pub table_alignments: HashMap<String, Vec<ColumnAlignment>>,
+    /// Users mentions are resolved against. Mentions of other users keep the name Notion
+    /// put in the mention.
+    pub users: Option<Arc<UserDirectory>>,
+    /// How `@user` mentions are written, with `{name}`, `{email}`, `{handle}` and `{id}`
+    /// replaced by those of the user, such as `[@{name}](mailto:{email})`. A mention of a
+    /// user without a field the template uses, such as a bot without an email address, is
+    /// written as the user's name.
+    pub user_mention_template: String,
+    /// Text of a mention of a user whose name is unknown, such as a removed user.
+    pub unknown_user_placeholder: String,
 }
 
 impl Default for MarkdownRenderOptions {
@@ -90,11 +202,119 @@ impl Default for MarkdownRenderOptions {
         Self {
             child_page_link_target: ChildLinkTarget::Notion,
             child_database_link_target: ChildLinkTarget::Notion,
+            synced_block_markers: false,
+            column_layout: ColumnLayout::Sequential,
+            block_anchors: false,
+            page_registry: None,
+            current_page_path: None,
+            inaccessible_link_placeholder: DEFAULT_INACCESSIBLE_LINK_PLACEHOLDER.into(),
+            mermaid_target: MermaidTarget::GitHub,
+            callout_style: CalloutStyle::Blockquote,
+            math_delimiters: MathDelimiters::Dollars,
+            compact_tables: false,
+            infer_table_alignment: false,
+            table_alignments: HashMap::new(),
+            users: None,
+            user_mention_template: DEFAULT_USER_MENTION_TEMPLATE.into(),
+            unknown_user_placeholder: DEFAULT_UNKNOWN_USER_PLACEHOLDER.into(),
         }
     }
 }
 
-#[derive(Deserialize, Clone, Debug)]
+impl MarkdownRenderOptions {
+    /// The alignment override of the table with ID `table_id`, dashed or not.
+    pub(crate) fn table_alignment(&self, table_id: &str) -> Option<&[ColumnAlignment]> {
+        if let Some(alignments) = self.table_alignments.get(table_id) {
+            return Some(alignments);
+        }
+        let table_id = normalize_page_id(table_id);
+        self.table_alignments
+            .iter()
+            .find(|(id, _)| normalize_page_id(id) == table_id)
+            .map(|(_, alignments)| alignments.as_slice())
+    }
+
+    /// Markdown of a mention of the user with ID `user_id`, whose name in the mention is
+    /// `name`. The fields of the user are escaped, the template is Markdown already.
+    pub(crate) fn user_mention(&self, user_id: &str, name: Option<&str>) -> String {
+        let user = self.users.as_ref().and_then(|users| users.get(user_id));
+        let Some(name) = user.and_then(|user| user.name.as_deref()).or(name) else {
+            return self.unknown_user_placeholder.clone();
+        };
+        let email = user.and_then(|user| user.email.as_deref());
+        let handle = self
+            .users
+            .as_ref()
+            .and_then(|users| users.handle(user_id, email));
+
+        let template = &self.user_mention_template;
+        let fields = [
+            ("{name}", Some(name)),
+            ("{email}", email),
+            ("{handle}", handle),
+            ("{id}", Some(user_id)),
+        ];
+        if fields
+            .iter()
+            .any(|(field, value)| value.is_none() && template.contains(field))
+        {
+            return escape_markdown(name);
+        }
+
+        // One pass over the template, so fields containing a placeholder stay as they are.
+        let mut mention = String::with_capacity(template.len());
+        let mut rest = template.as_str();
+        while let Some(start) = rest.find('{') {
+            mention.push_str(&rest[..start]);
+            rest = &rest[start..];
+            match fields.iter().find(|(field, _)| rest.starts_with(field)) {
+                Some((field, value)) => {
+                    mention.push_str(&escape_markdown(value.unwrap_or_default()));
+                    rest = &rest[field.len()..];
+                }
+                None => {
+                    mention.push('{');
+                    rest = &rest[1..];
+                }
+            }
+        }
+        mention.push_str(rest);
+        mention
+    }
+
+    /// Path of an exported page relative to the current page, if it is in the registry,
+    /// encoded as a link destination.
+    pub(crate) fn local_page_path(&self, page_id: &str) -> Option<String> {
+        let path = self.page_registry.as_ref()?.path(page_id)?;
+        Some(self.local_link(path))
+    }
+
+    /// `path`, relative to the export root, as a link from the current page.
+    pub(crate) fn local_link(&self, path: &str) -> String {
+        encode_path(&match &self.current_page_path {
+            Some(current) => relative_path(current, path),
+            None => path.to_string(),
+        })
+    }
+
+    /// Where a link to a page should point when there is a page registry: the local file of
+    /// an exported page, the Notion URL of any other. `None` without a registry.
+    pub(crate) fn page_link(&self, page_id: &str) -> Option<String> {
+        self.page_registry.as_ref()?;
+
+        self.local_page_path(page_id)
+            .or_else(|| Some(format!("{}/{}", NOTION_ORIGIN, page_id)))
+    }
+
+    /// `href` itself, or the local path when it is a Notion URL of an exported page.
+    pub(crate) fn resolve_href(&self, href: &str) -> String {
+        page_id_from_url(href)
+            .and_then(|page_id| self.local_page_path(&page_id))
+            .unwrap_or_else(|| href.to_string())
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "snake_case", tag = "type")]
 pub enum Block {
     NumberedListItem(NumberedListItem),
@@ -132,42 +352,57 @@ pub enum Block {
     TableOfContents(TableOfContents),
     Template(Template),
     Unsupported,
+    Unparsed(Unparsed),
     #[serde(other)]
     Unexpected,
 }
 
-#[derive(Deserialize, Clone, Debug)]
-#[serde(rename_all = "snake_case")]
+/// Fields the API returns on every block object, whatever its type.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
 #[serde(default)]
-pub struct BlockMeta {
-    order: usize,
-    depth: usize,
+pub struct BlockEnvelope {
+    pub id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created_time: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_edited_time: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created_by: Option<PartialUser>,
+    pub archived: bool,
+    pub has_children: bool,
 }
 
-impl Default for BlockMeta {
-    fn default() -> BlockMeta {
-        BlockMeta { order: 1, depth: 0 }
-    }
+/// A user as referenced from `created_by`, with only its ID.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct PartialUser {
+    pub id: String,
 }
 
-impl Block {
-    fn with_meta(self, meta: BlockMeta) -> Block {
-        match self {
-            Block::NumberedListItem(item) => Block::NumberedListItem(item.with_meta(meta)),
-            Block::BulletedListItem(item) => Block::BulletedListItem(item.with_meta(meta)),
-            Block::ToDo(item) => Block::ToDo(item.with_meta(meta)),
-            Block::Table(item) => Block::Table(item.with_meta(meta)),
-            Block::Column(item) => Block::Column(item.with_meta(meta)),
-            Block::ColumnList(item) => Block::ColumnList(item.with_meta(meta)),
-            Block::SyncedBlock(item) => Block::SyncedBlock(item.with_meta(meta)),
-            Block::Toggle(item) => Block::Toggle(item.with_meta(meta)),
-            Block::Heading1(item) => Block::Heading1(item.with_meta(meta)),
-            Block::Heading2(item) => Block::Heading2(item.with_meta(meta)),
-            Block::Heading3(item) => Block::Heading3(item.with_meta(meta)),
-            _ => self,
+impl BlockEnvelope {
+    fn anchor(&self, options: &MarkdownRenderOptions) -> String {
+        if options.block_anchors && !self.id.is_empty() {
+            format!("<a id=\"{}\"></a>", self.id)
+        } else {
+            String::new()
         }
     }
+}
+
+/// Where a block is rendered: its place among its siblings.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct RenderContext {
+    /// Position of a numbered list item in its list, the run of numbered items it belongs
+    /// to, starting at 1. Always 1 for other blocks.
+    pub order: usize,
+}
 
+impl Default for RenderContext {
+    fn default() -> RenderContext {
+        RenderContext { order: 1 }
+    }
+}
+
+impl Block {
     pub fn append(&mut self, child: Block) {
         match self {
             Block::Table(item) => item.append(child),
@@ -181,83 +416,560 @@ impl Block {
             Block::Heading1(item) => item.append(child),
             Block::Heading2(item) => item.append(child),
             Block::Heading3(item) => item.append(child),
+            Block::Unparsed(item) => item.append(child),
             _ => {}
         }
     }
 
+    pub fn children(&self) -> &[Block] {
+        match self {
+            Block::Table(item) => &item.children,
+            Block::ToDo(item) => &item.children,
+            Block::BulletedListItem(item) => &item.children,
+            Block::NumberedListItem(item) => &item.children,
+            Block::ColumnList(item) => &item.children,
+            Block::Column(item) => &item.children,
+            Block::SyncedBlock(item) => &item.children,
+            Block::Toggle(item) => &item.children,
+            Block::Heading1(item) => &item.children,
+            Block::Heading2(item) => &item.children,
+            Block::Heading3(item) => &item.children,
+            Block::Unparsed(item) => &item.children,
+            _ => &[],
+        }
+    }
+
+    pub fn children_mut(&mut self) -> &mut [Block] {
+        match self {
+            Block::Table(item) => &mut item.children,
+            Block::ToDo(item) => &mut item.children,
+            Block::BulletedListItem(item) => &mut item.children,
+            Block::NumberedListItem(item) => &mut item.children,
+            Block::ColumnList(item) => &mut item.children,
+            Block::Column(item) => &mut item.children,
+            Block::SyncedBlock(item) => &mut item.children,
+            Block::Toggle(item) => &mut item.children,
+            Block::Heading1(item) => &mut item.children,
+            Block::Heading2(item) => &mut item.children,
+            Block::Heading3(item) => &mut item.children,
+            Block::Unparsed(item) => &mut item.children,
+            _ => &mut [],
+        }
+    }
+
+    /// The fields common to all blocks. `None` for blocks the crate could not read.
+    pub fn envelope(&self) -> Option<&BlockEnvelope> {
+        match self {
+            Block::NumberedListItem(item) => Some(&item.envelope),
+            Block::BulletedListItem(item) => Some(&item.envelope),
+            Block::Paragraph(item) => Some(&item.envelope),
+            Block::Pdf(item) => Some(&item.envelope),
+            Block::Quote(item) => Some(&item.envelope),
+            Block::Code(item) => Some(&item.envelope),
+            Block::Heading1(item) => Some(&item.envelope),
+            Block::Heading2(item) => Some(&item.envelope),
+            Block::Heading3(item) => Some(&item.envelope),
+            Block::Image(item) => Some(&item.envelope),
+            Block::Divider(item) => Some(&item.envelope),
+            Block::File(item) => Some(&item.envelope),
+            Block::ToDo(item) => Some(&item.envelope),
+            Block::Bookmark(item) => Some(&item.envelope),
+            Block::Callout(item) => Some(&item.envelope),
+            Block::ChildPage(item) => Some(&item.envelope),
+            Block::Equation(item) => Some(&item.envelope),
+            Block::Table(item) => Some(&item.envelope),
+            Block::TableRow(item) => Some(&item.envelope),
+            Block::Embed(item) => Some(&item.envelope),
+            Block::LinkPreview(item) => Some(&item.envelope),
+            Block::LinkToPage(item) => Some(&item.envelope),
+            Block::ChildDatabase(item) => Some(&item.envelope),
+            Block::ColumnList(item) => Some(&item.envelope),
+            Block::Column(item) => Some(&item.envelope),
+            Block::Breadcrumb(item) => Some(&item.envelope),
+            Block::SyncedBlock(item) => Some(&item.envelope),
+            Block::Toggle(item) => Some(&item.envelope),
+            Block::Video(item) => Some(&item.envelope),
+            Block::TableOfContents(item) => Some(&item.envelope),
+            Block::Template(item) => Some(&item.envelope),
+            Block::Unsupported | Block::Unparsed(_) | Block::Unexpected => None,
+        }
+    }
+
+    pub fn id(&self) -> Option<&str> {
+        match self {
+            Block::Unparsed(item) => item.id(),
+            _ => self.envelope().map(|envelope| envelope.id.as_str()),
+        }
+    }
+
     pub fn to_markdown(&self) -> String {
+        self.to_markdown_with_options(&MarkdownRenderOptions::default())
+    }
+
+    /// Like [`Block::to_markdown_with_options`], but fails on a tree the Markdown can only
+    /// approximate instead of rendering it as closely as it can.
+    pub fn try_to_markdown_with_options(
+        &self,
+        options: &MarkdownRenderOptions,
+    ) -> Result<String, RenderError> {
+        check_blocks(std::slice::from_ref(self), None)?;
+        Ok(self.to_markdown_with_options(options))
+    }
+
+    pub fn to_markdown_with_options(&self, options: &MarkdownRenderOptions) -> String {
+        let mut markdown = String::new();
+        // Writing to a `String` never fails.
+        let _ = self.write_markdown(&mut markdown, RenderContext::default(), options);
+        markdown
+    }
+
+    /// Writes the block without the indentation of its first line, which the caller writes.
+    pub(crate) fn write_markdown(
+        &self,
+        out: &mut dyn fmt::Write,
+        context: RenderContext,
+        options: &MarkdownRenderOptions,
+    ) -> fmt::Result {
         match &self {
-            Block::NumberedListItem(item) => item.to_markdown(),
-            Block::BulletedListItem(item) => item.to_markdown(),
-            Block::ToDo(item) => item.to_markdown(),
-            Block::Table(item) => item.to_markdown(),
-            Block::Paragraph(item) => item.to_markdown(),
-            Block::Pdf(item) => item.to_markdown(),
-            Block::Quote(item) => item.to_markdown(),
-            Block::Code(item) => item.to_markdown(),
-            Block::Heading1(item) => item.to_markdown(),
-            Block::Heading2(item) => item.to_markdown(),
-            Block::Heading3(item) => item.to_markdown(),
-            Block::Image(item) => item.to_markdown(),
-            Block::Divider(item) => item.to_markdown(),
-            Block::File(item) => item.to_markdown(),
-            Block::Bookmark(item) => item.to_markdown(),
-            Block::Equation(item) => item.to_markdown(),
-            Block::Callout(item) => item.to_markdown(),
-            Block::ChildPage(item) => item.to_markdown(),
-            Block::Embed(item) => item.to_markdown(),
-            Block::LinkPreview(item) => item.to_markdown(),
-            Block::LinkToPage(item) => item.to_markdown(),
-            Block::ChildDatabase(item) => item.to_markdown(),
-            Block::Column(item) => item.to_markdown(),
-            Block::ColumnList(item) => item.to_markdown(),
-            Block::Breadcrumb(item) => item.to_markdown(),
-            Block::SyncedBlock(item) => item.to_markdown(),
-            Block::Toggle(item) => item.to_markdown(),
-            Block::Template(item) => item.to_markdown(),
-            Block::TableOfContents(item) => item.to_markdown(),
-            Block::Video(item) => item.to_markdown(),
-            Block::Unsupported => UNSUPPORTED_NODE_TEXT.into(),
-            Block::Unexpected => UNEXPECTED_NODE_TEXT.into(),
-            Block::TableRow(_) => panic!(
-                "The method to_markdown for Block::TableRow is not allowed. Please append rows to table as children."
-            ),
+            Block::NumberedListItem(item) => item.write_markdown(out, context, options),
+            Block::BulletedListItem(item) => item.write_markdown(out, context, options),
+            Block::ToDo(item) => item.write_markdown(out, context, options),
+            Block::Table(item) => item.write_markdown(out, context, options),
+            Block::Paragraph(item) => item.write_markdown(out, context, options),
+            Block::Pdf(item) => item.write_markdown(out, context, options),
+            Block::Quote(item) => item.write_markdown(out, context, options),
+            Block::Code(item) => item.write_markdown(out, context, options),
+            Block::Heading1(item) => item.write_markdown(out, context, options),
+            Block::Heading2(item) => item.write_markdown(out, context, options),
+            Block::Heading3(item) => item.write_markdown(out, context, options),
+            Block::Image(item) => item.write_markdown(out, context, options),
+            Block::Divider(item) => item.write_markdown(out, context, options),
+            Block::File(item) => item.write_markdown(out, context, options),
+            Block::Bookmark(item) => item.write_markdown(out, context, options),
+            Block::Equation(item) => item.write_markdown(out, context, options),
+            Block::Callout(item) => item.write_markdown(out, context, options),
+            Block::ChildPage(item) => item.write_markdown(out, context, options),
+            Block::Embed(item) => item.write_markdown(out, context, options),
+            Block::LinkPreview(item) => item.write_markdown(out, context, options),
+            Block::LinkToPage(item) => item.write_markdown(out, context, options),
+            Block::ChildDatabase(item) => item.write_markdown(out, context, options),
+            Block::Column(item) => item.write_markdown(out, context, options),
+            Block::ColumnList(item) => item.write_markdown(out, context, options),
+            Block::Breadcrumb(item) => item.write_markdown(out, context, options),
+            Block::SyncedBlock(item) => item.write_markdown(out, context, options),
+            Block::Toggle(item) => item.write_markdown(out, context, options),
+            Block::Template(item) => item.write_markdown(out, context, options),
+            Block::TableOfContents(item) => item.write_markdown(out, context, options),
+            Block::Video(item) => item.write_markdown(out, context, options),
+            Block::Unparsed(item) => item.write_markdown(out, context, options),
+            Block::Unsupported => out.write_str(UNSUPPORTED_NODE_TEXT),
+            Block::Unexpected => out.write_str(UNEXPECTED_NODE_TEXT),
+            Block::TableRow(item) => item.write_markdown(out, context, options),
         }
     }
 }
 
 trait MarkdownBlock {
-    fn to_markdown(&self) -> String;
+    fn to_markdown(&self, options: &MarkdownRenderOptions) -> String;
+}
+
+/// Rendering into a writer. Blocks with children implement it directly so their children are
+/// written as they are rendered, instead of collected into nested strings.
+trait WriteMarkdown {
+    fn write_markdown(
+        &self,
+        out: &mut dyn fmt::Write,
+        context: RenderContext,
+        options: &MarkdownRenderOptions,
+    ) -> fmt::Result;
 }
 
-#[derive(Deserialize, Clone, Debug)]
+impl<T: MarkdownBlock> WriteMarkdown for T {
+    fn write_markdown(
+        &self,
+        out: &mut dyn fmt::Write,
+        _context: RenderContext,
+        options: &MarkdownRenderOptions,
+    ) -> fmt::Result {
+        out.write_str(&self.to_markdown(options))
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "snake_case")]
 pub(crate) struct BlockContent {
     pub rich_text: Vec<RichText>,
 }
 
 pub trait BlockChildren {
+    /// Renders the blocks with every line indented by two spaces per level of `depth`.
     fn to_markdown(&self, depth: usize) -> String;
+
+    fn to_markdown_with_options(&self, depth: usize, options: &MarkdownRenderOptions) -> String;
+
+    /// Like [`BlockChildren::to_markdown_with_options`], but fails on a tree the Markdown can
+    /// only approximate instead of rendering it as closely as it can.
+    fn try_to_markdown_with_options(
+        &self,
+        depth: usize,
+        options: &MarkdownRenderOptions,
+    ) -> Result<String, RenderError>;
+
+    /// Writes the blocks into `out` as they are rendered, without building the document in
+    /// memory first.
+    fn write_markdown(
+        &self,
+        out: &mut impl fmt::Write,
+        depth: usize,
+        options: &MarkdownRenderOptions,
+    ) -> fmt::Result;
+
+    /// Like [`BlockChildren::write_markdown`], into a file or another byte stream. Small
+    /// writes go straight to `out`, so wrap unbuffered streams in an `io::BufWriter`.
+    fn write_markdown_to(
+        &self,
+        out: &mut impl io::Write,
+        depth: usize,
+        options: &MarkdownRenderOptions,
+    ) -> io::Result<()>;
 }
 
 impl BlockChildren for Vec<Block> {
     fn to_markdown(&self, depth: usize) -> String {
+        self.to_markdown_with_options(depth, &MarkdownRenderOptions::default())
+    }
+
+    fn to_markdown_with_options(&self, depth: usize, options: &MarkdownRenderOptions) -> String {
         let mut markdown = String::new();
+        // Writing to a `String` never fails.
+        let _ = write_document(self, &mut markdown, depth * INDENT.len(), options);
+        markdown
+    }
 
-        for (index, block) in self.iter().enumerate() {
-            let rendered = block
-                .clone()
-                .with_meta(BlockMeta {
-                    order: index + 1,
-                    depth,
-                })
-                .to_markdown();
-
-            markdown.push_str(&INDENT.repeat(depth));
-            markdown.push_str(&rendered);
-            markdown.push('\n');
+    fn try_to_markdown_with_options(
+        &self,
+        depth: usize,
+        options: &MarkdownRenderOptions,
+    ) -> Result<String, RenderError> {
+        check_blocks(self, None)?;
+        Ok(self.to_markdown_with_options(depth, options))
+    }
+
+    fn write_markdown(
+        &self,
+        out: &mut impl fmt::Write,
+        depth: usize,
+        options: &MarkdownRenderOptions,
+    ) -> fmt::Result {
+        write_document(self, out, depth * INDENT.len(), options)
+    }
+
+    fn write_markdown_to(
+        &self,
+        out: &mut impl io::Write,
+        depth: usize,
+        options: &MarkdownRenderOptions,
+    ) -> io::Result<()> {
+        let mut adapter = IoAdapter { out, error: None };
+        match write_document(self, &mut adapter, depth * INDENT.len(), options) {
+            Ok(()) => Ok(()),
+            Err(fmt::Error) => Err(adapter
+                .error
+                .unwrap_or_else(|| io::Error::other("formatting failed"))),
         }
+    }
+}
 
-        markdown
+/// Finds the first block the renderer has to approximate: table rows outside a table, and
+/// anything but rows inside one. `table_id` is the ID of the table `blocks` belong to.
+fn check_blocks(blocks: &[Block], table_id: Option<&str>) -> Result<(), RenderError> {
+    for block in blocks {
+        match (block, table_id) {
+            (Block::TableRow(_), Some(_)) => {}
+            (Block::TableRow(_), None) => {
+                return Err(RenderError::TableRowOutsideTable {
+                    block_id: block.id().map(String::from),
+                });
+            }
+            (_, Some(table_id)) => {
+                return Err(RenderError::BlockInsideTable {
+                    table_id: table_id.into(),
+                    block_id: block.id().map(String::from),
+                });
+            }
+            (Block::Table(table), None) => {
+                check_blocks(&table.children, Some(&table.envelope.id))?;
+            }
+            _ => check_blocks(block.children(), None)?,
+        }
+    }
+
+    Ok(())
+}
+
+/// An HTML comment holding `text`, which could otherwise end the comment early with `--` or
+/// `>`, such as a block ID from a malformed response.
+pub(crate) fn html_comment(text: &str) -> String {
+    let mut text = text.replace('>', "&gt;");
+    while text.contains("--") {
+        text = text.replace("--", "- -");
+    }
+
+    format!("<!-- {} -->", text)
+}
+
+/// The lists whose items Markdown joins into one list when they follow each other.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum ListKind {
+    /// Bulleted list items and to-dos, both written with `-`.
+    Dash,
+    Numbered,
+}
+
+fn list_kind(block: &Block) -> Option<ListKind> {
+    match block {
+        Block::BulletedListItem(_) | Block::ToDo(_) => Some(ListKind::Dash),
+        Block::NumberedListItem(_) => Some(ListKind::Numbered),
+        _ => None,
+    }
+}
+
+/// What goes between the text of a list item and its children: a nested list starts on the
+/// next line, anything else after a blank line so that it doesn't continue the item's text.
+fn list_item_children_separator(children: &[Block]) -> &'static str {
+    match children.first().and_then(list_kind) {
+        Some(_) => "\n",
+        None => "\n\n",
+    }
+}
+
+/// Writes the blocks one after another, numbering every run of numbered list items from 1,
+/// without a line break after the last one.
+///
+/// Items of the same list go on consecutive lines; any other two blocks are set apart by a
+/// blank line, so that paragraphs don't run into each other or into a list.
+fn write_blocks(
+    blocks: &[Block],
+    out: &mut dyn fmt::Write,
+    options: &MarkdownRenderOptions,
+) -> fmt::Result {
+    let mut previous: Option<&Block> = None;
+    let mut order = 0;
+    for block in blocks {
+        if let Some(previous) = previous {
+            let same_list =
+                list_kind(previous).is_some() && list_kind(previous) == list_kind(block);
+            out.write_str(if same_list { "\n" } else { "\n\n" })?;
+        }
+        order = match block {
+            Block::NumberedListItem(_) => order + 1,
+            _ => 0,
+        };
+        let context = RenderContext {
+            order: order.max(1),
+        };
+        block.write_markdown(out, context, options)?;
+        previous = Some(block);
+    }
+
+    Ok(())
+}
+
+/// Writes a whole document, ending it with a line break unless it is empty.
+fn write_document(
+    blocks: &[Block],
+    out: &mut dyn fmt::Write,
+    width: usize,
+    options: &MarkdownRenderOptions,
+) -> fmt::Result {
+    write_indented(blocks, out, width, options)?;
+    if !blocks.is_empty() {
+        out.write_char('\n')?;
+    }
+
+    Ok(())
+}
+
+/// Like [`write_blocks`], with every line of the blocks indented by `width` spaces, as the
+/// content of a list item is indented to line up with the text after its marker.
+fn write_indented(
+    blocks: &[Block],
+    out: &mut dyn fmt::Write,
+    width: usize,
+    options: &MarkdownRenderOptions,
+) -> fmt::Result {
+    if width == 0 {
+        return write_blocks(blocks, out, options);
+    }
+
+    let mut indented = Indented {
+        out,
+        width,
+        at_line_start: true,
+    };
+    write_blocks(blocks, &mut indented, options)
+}
+
+/// Indents each line written through it by `width` spaces, leaving blank lines empty.
+struct Indented<'w> {
+    out: &'w mut dyn fmt::Write,
+    width: usize,
+    at_line_start: bool,
+}
+
+impl fmt::Write for Indented<'_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for line in s.split_inclusive('\n') {
+            if self.at_line_start && line != "\n" {
+                write!(self.out, "{:width$}", "", width = self.width)?;
+            }
+            self.out.write_str(line)?;
+            self.at_line_start = line.ends_with('\n');
+        }
+
+        Ok(())
+    }
+}
+
+/// Forwards formatted output to an `io::Write`, keeping the I/O error `fmt::Error` cannot
+/// carry.
+struct IoAdapter<'w, W: io::Write> {
+    out: &'w mut W,
+    error: Option<io::Error>,
+}
+
+impl<W: io::Write> fmt::Write for IoAdapter<'_, W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.out.write_all(s.as_bytes()).map_err(|e| {
+            self.error = Some(e);
+            fmt::Error
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use std::io;
+
+    use super::{Block, BlockChildren, BlockEnvelope, MarkdownRenderOptions, PartialUser};
+
+    #[test]
+    fn test_envelope() {
+        let block: Block =
+            serde_json::from_str(include_str!("tests/block/toggle_response.json")).unwrap();
+
+        assert_eq!(
+            block.envelope(),
+            Some(&BlockEnvelope {
+                id: "XXXXXXXX-XXXX-XXXX-XXXX-XXXXXXXXXXXX".into(),
+                created_time: Some("2025-05-17T20:45:00.000Z".into()),
+                last_edited_time: Some("2025-05-17T20:45:00.000Z".into()),
+                created_by: Some(PartialUser {
+                    id: "XXXXXXXX-XXXX-XXXX-XXXX-XXXXXXXXXXXX".into()
+                }),
+                archived: false,
+                has_children: true,
+            })
+        );
+    }
+
+    #[test]
+    fn test_block_anchors() {
+        let block: Block =
+            serde_json::from_str(include_str!("tests/block/headline1_response.json")).unwrap();
+        let options = MarkdownRenderOptions {
+            block_anchors: true,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            block.to_markdown_with_options(&options),
+            "# <a id=\"XXXXXXXX-XXXX-XXXX-XXXX-XXXXXXXXXXXX\"></a>this is headline1"
+        );
+    }
+
+    fn nested_list() -> Vec<Block> {
+        let mut item: Block =
+            serde_json::from_str(include_str!("tests/block/numbered_list_item_response.json"))
+                .unwrap();
+        let mut child = item.clone();
+        child.append(item.clone());
+        item.append(child);
+        let toggle: Block =
+            serde_json::from_str(include_str!("tests/block/toggle_response.json")).unwrap();
+
+        vec![item.clone(), item, toggle]
+    }
+
+    #[test]
+    fn test_write_markdown_matches_to_markdown() {
+        let blocks = nested_list();
+        let options = MarkdownRenderOptions::default();
+
+        let mut written = String::new();
+        blocks.write_markdown(&mut written, 1, &options).unwrap();
+        let mut bytes = Vec::new();
+        blocks.write_markdown_to(&mut bytes, 1, &options).unwrap();
+
+        let expected = blocks.to_markdown_with_options(1, &options);
+        assert_eq!(written, expected);
+        assert_eq!(String::from_utf8(bytes).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_write_markdown_to_returns_io_error() {
+        struct Full;
+
+        impl io::Write for Full {
+            fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+                Err(io::Error::new(io::ErrorKind::StorageFull, "disk full"))
+            }
+
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let err = nested_list()
+            .write_markdown_to(&mut Full, 0, &MarkdownRenderOptions::default())
+            .unwrap_err();
+
+        assert_eq!(err.kind(), io::ErrorKind::StorageFull);
+    }
+
+    #[rstest]
+    #[case(include_str!("tests/block/bookmark_response.json"))]
+    #[case(include_str!("tests/block/callout_response.json"))]
+    #[case(include_str!("tests/block/child_page_response.json"))]
+    #[case(include_str!("tests/block/code_response.json"))]
+    #[case(include_str!("tests/block/column_response.json"))]
+    #[case(include_str!("tests/block/embed_response.json"))]
+    #[case(include_str!("tests/block/equation_response.json"))]
+    #[case(include_str!("tests/block/file_response.json"))]
+    #[case(include_str!("tests/block/headline1_response.json"))]
+    #[case(include_str!("tests/block/image_response.json"))]
+    #[case(include_str!("tests/block/link_preview_response.json"))]
+    #[case(include_str!("tests/block/link_to_page_response.json"))]
+    #[case(include_str!("tests/block/pdf_response.json"))]
+    #[case(include_str!("tests/block/synced_block_response.json"))]
+    #[case(include_str!("tests/block/table_response.json"))]
+    #[case(include_str!("tests/block/checked_to_do_response.json"))]
+    #[case(include_str!("tests/block/video_response.json"))]
+    fn test_serialize_round_trip(#[case] json: &str) {
+        let mut block: Block = serde_json::from_str(json).unwrap();
+        if let Block::Table(_) = block {
+            block.append(
+                serde_json::from_str(include_str!("tests/block/table_row_response.json")).unwrap(),
+            );
+        }
+
+        let serialized = serde_json::to_string(&block).unwrap();
+        let parsed: Block = serde_json::from_str(&serialized).unwrap();
+
+        assert_eq!(vec![parsed].to_markdown(0), vec![block].to_markdown(0));
     }
 }
@@ -1,20 +1,22 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
-use super::MarkdownBlock;
+use super::{BlockEnvelope, MarkdownBlock, MarkdownRenderOptions};
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "snake_case")]
 pub struct Bookmark {
+    #[serde(flatten)]
+    pub(crate) envelope: BlockEnvelope,
     bookmark: BookmarkContent,
 }
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 
 struct BookmarkContent {
     url: String,
 }
 
 impl MarkdownBlock for Bookmark {
-    fn to_markdown(&self) -> String {
+    fn to_markdown(&self, _options: &MarkdownRenderOptions) -> String {
         format!("[Bookmark: {}]({})", self.bookmark.url, self.bookmark.url)
     }
 }
@@ -1,13 +1,16 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
-use super::{BREADCRUMB_NODE_TEXT, MarkdownBlock};
+use super::{BREADCRUMB_NODE_TEXT, BlockEnvelope, MarkdownBlock, MarkdownRenderOptions};
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "snake_case")]
-pub struct Breadcrumb {}
+pub struct Breadcrumb {
+    #[serde(flatten)]
+    pub(crate) envelope: BlockEnvelope,
+}
 
 impl MarkdownBlock for Breadcrumb {
-    fn to_markdown(&self) -> String {
+    fn to_markdown(&self, _options: &MarkdownRenderOptions) -> String {
         BREADCRUMB_NODE_TEXT.into()
     }
 }
@@ -1,43 +1,53 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
-use crate::{block::BlockChildren, rich_text::RichTextVec};
+use std::fmt;
 
-use super::{Block, BlockContent, BlockMeta, MarkdownBlock};
+use crate::rich_text::RichTextVec;
 
-#[derive(Deserialize, Clone, Debug)]
+use super::{
+    Block, BlockContent, BlockEnvelope, MarkdownRenderOptions, RenderContext, WriteMarkdown,
+    list_item_children_separator, write_indented,
+};
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "snake_case")]
 pub struct BulletedListItem {
+    #[serde(flatten)]
+    pub(crate) envelope: BlockEnvelope,
     bulleted_list_item: BlockContent,
-    #[serde(skip_serializing, default)]
-    children: Vec<Block>,
-    #[serde(skip_serializing, default)]
-    meta: BlockMeta,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) children: Vec<Block>,
 }
 
 impl BulletedListItem {
     pub(crate) fn append(&mut self, child: Block) {
         self.children.push(child);
     }
-
-    pub(crate) fn with_meta(self, meta: BlockMeta) -> BulletedListItem {
-        BulletedListItem {
-            meta,
-            children: self.children,
-            bulleted_list_item: self.bulleted_list_item,
-        }
-    }
 }
 
-impl MarkdownBlock for BulletedListItem {
-    fn to_markdown(&self) -> String {
-        let inline = self.bulleted_list_item.rich_text.to_markdown();
+impl WriteMarkdown for BulletedListItem {
+    fn write_markdown(
+        &self,
+        out: &mut dyn fmt::Write,
+        _context: RenderContext,
+        options: &MarkdownRenderOptions,
+    ) -> fmt::Result {
+        let marker = "- ";
+        write!(
+            out,
+            "{}{}{}",
+            marker,
+            self.envelope.anchor(options),
+            self.bulleted_list_item
+                .rich_text
+                .to_markdown_with_options(options)
+        )?;
 
-        if self.children.is_empty() {
-            format!("- {}", inline)
-        } else {
-            let children_markdown = self.children.to_markdown(self.meta.depth + 1);
-            format!("- {}\n{}", inline, children_markdown)
+        if !self.children.is_empty() {
+            out.write_str(list_item_children_separator(&self.children))?;
+            write_indented(&self.children, out, marker.len(), options)?;
         }
+        Ok(())
     }
 }
 
@@ -69,9 +79,7 @@ mod test {
               - this is bulleted list item
                 - this is bulleted list item
                   - this is bulleted list item
-
                 - this is bulleted list item
-
             "#},
         )
     }
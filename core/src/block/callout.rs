@@ -1,37 +1,58 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 use crate::rich_text::{RichText, RichTextVec};
 
-use super::MarkdownBlock;
+use super::{BlockEnvelope, CalloutStyle, MarkdownBlock, MarkdownRenderOptions};
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "snake_case")]
 pub struct Callout {
+    #[serde(flatten)]
+    pub(crate) envelope: BlockEnvelope,
     callout: CalloutContent,
 }
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 struct CalloutContent {
     icon: IconContent,
     rich_text: Vec<RichText>,
 }
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 
 struct IconContent {
     emoji: String,
 }
 
-impl MarkdownBlock for Callout {
-    fn to_markdown(&self) -> String {
+impl Callout {
+    /// The callout text, led by its icon.
+    fn text(&self, options: &MarkdownRenderOptions) -> String {
+        let text = self.envelope.anchor(options)
+            + &self.callout.rich_text.to_markdown_with_options(options);
         if self.callout.icon.emoji.is_empty() {
-            format!("> {}", self.callout.rich_text.to_markdown())
+            text
         } else {
-            format!(
-                "> {} {}",
-                self.callout.icon.emoji,
-                self.callout.rich_text.to_markdown()
-            )
+            format!("{} {}", self.callout.icon.emoji, text)
+        }
+    }
+}
+
+/// Prefixes every line of `text` with `prefix`.
+fn indent_lines(text: &str, prefix: &str) -> String {
+    text.lines()
+        .map(|line| format!("{}{}", prefix, line))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+impl MarkdownBlock for Callout {
+    fn to_markdown(&self, options: &MarkdownRenderOptions) -> String {
+        let text = self.text(options);
+        match options.callout_style {
+            CalloutStyle::Blockquote => format!("> {}", text),
+            CalloutStyle::GitHubAlert => format!("> [!NOTE]\n{}", indent_lines(&text, "> ")),
+            CalloutStyle::MkDocs => format!("!!! note\n{}", indent_lines(&text, "    ")),
+            CalloutStyle::Docusaurus => format!(":::note\n{}\n:::", text),
         }
     }
 }
@@ -42,7 +63,7 @@ mod test {
     use indoc::indoc;
     use pretty_assertions::assert_eq;
 
-    use crate::block::Block;
+    use crate::block::{Block, CalloutStyle, MarkdownRenderOptions};
 
     #[test]
     fn test_to_markdown() {
@@ -56,4 +77,39 @@ mod test {
             "#}
         )
     }
+
+    #[test]
+    fn test_to_markdown_with_styles() {
+        let item: Block =
+            serde_json::from_str(include_str!("../tests/block/callout_response.json")).unwrap();
+        let render = |callout_style| {
+            item.to_markdown_with_options(&MarkdownRenderOptions {
+                callout_style,
+                ..Default::default()
+            }) + "\n"
+        };
+
+        assert_eq!(
+            render(CalloutStyle::GitHubAlert),
+            indoc! {r#"
+                > [!NOTE]
+                > ❗ this is callout
+            "#}
+        );
+        assert_eq!(
+            render(CalloutStyle::MkDocs),
+            indoc! {r#"
+                !!! note
+                    ❗ this is callout
+            "#}
+        );
+        assert_eq!(
+            render(CalloutStyle::Docusaurus),
+            indoc! {r#"
+                :::note
+                ❗ this is callout
+                :::
+            "#}
+        );
+    }
 }
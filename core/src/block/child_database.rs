@@ -1,35 +1,43 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
-use crate::{ChildLinkTarget, MarkdownRenderOptions, block::NOTION_ORIGIN, escape_page_title};
+use crate::{
+    ChildLinkTarget, MarkdownRenderOptions, block::NOTION_ORIGIN, escape_page_title,
+    page_registry::encode_path, rich_text::escape_markdown,
+};
 
-use super::MarkdownBlock;
+use super::{BlockEnvelope, MarkdownBlock};
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "snake_case")]
 pub struct ChildDatabase {
-    id: String,
+    #[serde(flatten)]
+    pub(crate) envelope: BlockEnvelope,
     child_database: ChildDatabaseContent,
 }
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 
 struct ChildDatabaseContent {
     title: String,
 }
 
 impl MarkdownBlock for ChildDatabase {
-    fn to_markdown(&self) -> String {
-        let options = MarkdownRenderOptions::default();
+    fn to_markdown(&self, options: &MarkdownRenderOptions) -> String {
+        let link = options.page_link(&self.envelope.id).unwrap_or_else(|| {
+            match options.child_database_link_target {
+                ChildLinkTarget::MarkdownFile => {
+                    let title = escape_page_title(&self.child_database.title);
 
-        let link = match options.child_database_link_target {
-            ChildLinkTarget::MarkdownFile => {
-                let title = escape_page_title(&self.child_database.title);
-
-                format!("{}.md", title)
+                    encode_path(&format!("{}.md", title))
+                }
+                ChildLinkTarget::Notion => format!("{}/{}", NOTION_ORIGIN, self.envelope.id),
             }
-            ChildLinkTarget::Notion => format!("{}/{}", NOTION_ORIGIN, self.id),
-        };
+        });
 
-        format!("[Child Database: {}]({})", self.child_database.title, link)
+        format!(
+            "[Child Database: {}]({})",
+            escape_markdown(&self.child_database.title),
+            link
+        )
     }
 }
 
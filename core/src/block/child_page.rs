@@ -1,39 +1,56 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 use crate::{
     MarkdownRenderOptions,
     block::{ChildLinkTarget, NOTION_ORIGIN},
     escape_page_title,
+    page_registry::encode_path,
+    rich_text::escape_markdown,
 };
 
-use super::MarkdownBlock;
+use super::{BlockEnvelope, MarkdownBlock};
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "snake_case")]
 pub struct ChildPage {
-    id: String,
+    #[serde(flatten)]
+    pub(crate) envelope: BlockEnvelope,
     child_page: ChildPageContent,
 }
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 
 struct ChildPageContent {
     title: String,
 }
 
-impl MarkdownBlock for ChildPage {
-    fn to_markdown(&self) -> String {
-        let options = MarkdownRenderOptions::default();
+impl ChildPage {
+    pub fn id(&self) -> &str {
+        &self.envelope.id
+    }
+
+    pub fn title(&self) -> &str {
+        &self.child_page.title
+    }
+}
 
-        let link = match options.child_page_link_target {
-            ChildLinkTarget::MarkdownFile => {
-                let title = escape_page_title(&self.child_page.title);
+impl MarkdownBlock for ChildPage {
+    fn to_markdown(&self, options: &MarkdownRenderOptions) -> String {
+        let link = options.page_link(&self.envelope.id).unwrap_or_else(|| {
+            match options.child_page_link_target {
+                ChildLinkTarget::MarkdownFile => {
+                    let title = escape_page_title(&self.child_page.title);
 
-                format!("{}.md", title)
+                    encode_path(&format!("{}.md", title))
+                }
+                ChildLinkTarget::Notion => format!("{}/{}", NOTION_ORIGIN, self.envelope.id),
             }
-            ChildLinkTarget::Notion => format!("{}/{}", NOTION_ORIGIN, self.id),
-        };
+        });
 
-        format!("[Child Page: {}]({})", self.child_page.title, link)
+        format!(
+            "[Child Page: {}]({})",
+            escape_markdown(&self.child_page.title),
+            link
+        )
     }
 }
 
@@ -42,8 +59,16 @@ mod test {
 
     use indoc::indoc;
     use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    use std::sync::Arc;
 
-    use crate::block::Block;
+    use crate::{
+        ChildLinkTarget, MarkdownRenderOptions, PageRegistry,
+        block::{Block, MarkdownBlock},
+    };
+
+    use super::ChildPage;
 
     #[test]
     fn test_to_markdown() {
@@ -70,4 +95,38 @@ mod test {
             "#}
         )
     }
+
+    #[test]
+    fn test_to_markdown_with_special_characters_in_title() {
+        let item: ChildPage = serde_json::from_value(json!({
+            "object": "block",
+            "id": "0123abcd-0123-abcd-0123-abcd0123abcd",
+            "type": "child_page",
+            "child_page": { "title": "Q&A #1 (100%)" }
+        }))
+        .unwrap();
+
+        assert_eq!(
+            item.to_markdown(&MarkdownRenderOptions {
+                child_page_link_target: ChildLinkTarget::MarkdownFile,
+                ..Default::default()
+            }),
+            "[Child Page: Q&A \\#1 (100%)](Q&A_%231_%28100%25%29.md)"
+        );
+
+        let mut registry = PageRegistry::new();
+        registry.insert(
+            "0123abcd0123abcd0123abcd0123abcd",
+            "Notes (2024)/Q&A #1 (100%).md",
+            "Q&A #1 (100%)",
+        );
+        assert_eq!(
+            item.to_markdown(&MarkdownRenderOptions {
+                page_registry: Some(Arc::new(registry)),
+                current_page_path: Some("Index.md".into()),
+                ..Default::default()
+            }),
+            "[Child Page: Q&A \\#1 (100%)](Notes%20%282024%29/Q&A%20%231%20%28100%25%29.md)"
+        );
+    }
 }
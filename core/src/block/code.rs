@@ -1,35 +1,137 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
-use crate::rich_text::{RichText, RichTextVec};
+use crate::rich_text::RichText;
 
-use super::MarkdownBlock;
+use super::{BlockEnvelope, MarkdownBlock, MarkdownRenderOptions};
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct Code {
+    #[serde(flatten)]
+    pub(crate) envelope: BlockEnvelope,
     code: CodeContent,
+    /// Path of the SVG the diagram in this block was rendered to, relative to the export root.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    diagram_path: Option<String>,
 }
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "snake_case")]
 pub struct CodeContent {
     rich_text: Vec<RichText>,
     language: String,
 }
 
+impl Code {
+    pub fn language(&self) -> &str {
+        &self.code.language
+    }
+
+    pub fn is_mermaid(&self) -> bool {
+        self.code.language.eq_ignore_ascii_case("mermaid")
+    }
+
+    /// The code as written, without any formatting.
+    pub fn source(&self) -> String {
+        self.code
+            .rich_text
+            .iter()
+            .map(RichText::plain_text)
+            .collect()
+    }
+
+    /// Renders the block as an image of the diagram at `path` instead of as code.
+    pub fn set_diagram_path(&mut self, path: impl Into<String>) {
+        self.diagram_path = Some(path.into());
+    }
+
+    fn mermaid_to_markdown(&self, options: &MarkdownRenderOptions) -> String {
+        if let Some(path) = &self.diagram_path {
+            return format!("![diagram]({})", options.local_link(path));
+        }
+
+        // Every target renders a `mermaid` fence, Material for MkDocs through a SuperFences
+        // custom fence that the generated `mkdocs.yml` sets up.
+        let source = self.source();
+        let fence = fence(&source);
+        format!("{}mermaid\n{}\n{}", fence, source, fence)
+    }
+}
+
 impl MarkdownBlock for Code {
-    fn to_markdown(&self) -> String {
-        let inline = self.code.rich_text.to_markdown();
-        format!("``` {}\n{}\n```", self.code.language, inline)
+    fn to_markdown(&self, options: &MarkdownRenderOptions) -> String {
+        if self.is_mermaid() {
+            return self.mermaid_to_markdown(options);
+        }
+
+        // Code is shown verbatim, so annotations and links inside it are dropped.
+        let source = self.source();
+        let fence = fence(&source);
+        format!(
+            "{}{}\n{}\n{}",
+            fence,
+            markdown_language(&self.code.language),
+            source,
+            fence
+        )
     }
 }
 
+/// A backtick fence longer than any backtick run in `code`, so the code cannot close it.
+pub(crate) fn fence(code: &str) -> String {
+    let longest_run = code.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+    "`".repeat((longest_run + 1).max(3))
+}
+
+/// Maps a Notion code language onto the identifier GitHub Linguist and highlight.js use
+/// for it. Languages named the same in both pass through unchanged.
+pub(crate) fn markdown_language(language: &str) -> String {
+    let language = language.to_lowercase();
+    let mapped = match language.as_str() {
+        "plain text" | "notion formula" => "text",
+        "assembly" => "asm",
+        "c#" => "csharp",
+        "c++" => "cpp",
+        "docker" => "dockerfile",
+        "f#" => "fsharp",
+        "flow" => "javascript",
+        "java/c/c++/c#" => "java",
+        "llvm ir" => "llvm",
+        "markup" => "html",
+        "objective-c" => "objectivec",
+        "shell" => "sh",
+        "vb.net" => "vbnet",
+        "visual basic" => "vb",
+        "webassembly" => "wasm",
+        other => return other.replace(' ', "-"),
+    };
+    mapped.to_string()
+}
+
 #[cfg(test)]
 mod test {
 
     use indoc::indoc;
     use pretty_assertions::assert_eq;
 
-    use crate::block::Block;
+    use crate::block::{Block, MarkdownRenderOptions, MermaidTarget};
+
+    use super::{Code, markdown_language};
+
+    fn mermaid() -> Code {
+        serde_json::from_value(serde_json::json!({
+            "type": "code",
+            "id": "code-id",
+            "code": {
+                "rich_text": [{
+                    "type": "text",
+                    "plain_text": "graph TD\n  A --> B",
+                    "href": null
+                }],
+                "language": "mermaid"
+            }
+        }))
+        .unwrap()
+    }
 
     #[test]
     fn test_to_markdown() {
@@ -39,10 +141,121 @@ mod test {
         assert_eq!(
             item.to_markdown() + "\n",
             indoc! {r#"
-                ``` markdown
+                ```markdown
                 this is markdown code
                 ```
             "#}
         )
     }
+
+    #[test]
+    fn test_markdown_language() {
+        assert_eq!(markdown_language("plain text"), "text");
+        assert_eq!(markdown_language("c++"), "cpp");
+        assert_eq!(markdown_language("objective-c"), "objectivec");
+        assert_eq!(markdown_language("markdown"), "markdown");
+        assert_eq!(markdown_language("Rust"), "rust");
+    }
+
+    #[test]
+    fn test_fence_is_longer_than_backtick_runs() {
+        let item: Block = serde_json::from_value(serde_json::json!({
+            "type": "code",
+            "id": "code-id",
+            "code": {
+                "rich_text": [{
+                    "type": "text",
+                    "plain_text": "```rust\nfn main() {}\n```",
+                    "href": null
+                }],
+                "language": "markdown"
+            }
+        }))
+        .unwrap();
+
+        assert_eq!(
+            item.to_markdown() + "\n",
+            indoc! {r#"
+                ````markdown
+                ```rust
+                fn main() {}
+                ```
+                ````
+            "#}
+        )
+    }
+
+    #[test]
+    fn test_annotations_are_dropped() {
+        let item: Block = serde_json::from_value(serde_json::json!({
+            "type": "code",
+            "id": "code-id",
+            "code": {
+                "rich_text": [
+                    {
+                        "type": "text",
+                        "plain_text": "let x = ",
+                        "href": null
+                    },
+                    {
+                        "type": "text",
+                        "plain_text": "`y`",
+                        "href": "https://example.com",
+                        "annotations": { "bold": true, "italic": false, "strikethrough": false, "code": true, "underline": false }
+                    }
+                ],
+                "language": "rust"
+            }
+        }))
+        .unwrap();
+
+        assert_eq!(
+            item.to_markdown() + "\n",
+            indoc! {r#"
+                ```rust
+                let x = `y`
+                ```
+            "#}
+        )
+    }
+
+    #[test]
+    fn test_mermaid_targets() {
+        let item = Block::Code(mermaid());
+        let render = |mermaid_target| {
+            item.to_markdown_with_options(&MarkdownRenderOptions {
+                mermaid_target,
+                ..Default::default()
+            }) + "\n"
+        };
+
+        assert_eq!(
+            render(MermaidTarget::GitHub),
+            indoc! {r#"
+                ```mermaid
+                graph TD
+                  A --> B
+                ```
+            "#}
+        );
+        assert_eq!(render(MermaidTarget::MkDocs), render(MermaidTarget::GitHub));
+        assert_eq!(
+            render(MermaidTarget::Docusaurus),
+            render(MermaidTarget::GitHub)
+        );
+    }
+
+    #[test]
+    fn test_mermaid_with_diagram() {
+        let mut item = mermaid();
+        item.set_diagram_path("docs/Page-1.svg");
+
+        assert_eq!(
+            Block::Code(item).to_markdown_with_options(&MarkdownRenderOptions {
+                current_page_path: Some("docs/Page.md".into()),
+                ..Default::default()
+            }),
+            "![diagram](Page-1.svg)"
+        );
+    }
 }
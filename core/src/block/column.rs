@@ -1,14 +1,25 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
-use super::{Block, BlockChildren, BlockMeta, MarkdownBlock};
+use std::fmt;
 
-#[derive(Deserialize, Clone, Debug)]
+use super::{
+    Block, BlockEnvelope, MarkdownRenderOptions, RenderContext, WriteMarkdown, write_blocks,
+};
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "snake_case")]
 pub struct Column {
-    #[serde(skip_serializing, default)]
-    children: Vec<Block>,
-    #[serde(skip_serializing, default)]
-    meta: BlockMeta,
+    #[serde(flatten)]
+    pub(crate) envelope: BlockEnvelope,
+    #[serde(default)]
+    column: ColumnContent,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) children: Vec<Block>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+struct ColumnContent {
+    width_ratio: Option<f64>,
 }
 
 impl Column {
@@ -16,17 +27,20 @@ impl Column {
         self.children.push(child);
     }
 
-    pub(crate) fn with_meta(self, meta: BlockMeta) -> Column {
-        Column {
-            meta,
-            children: self.children,
-        }
+    /// Share of the row this column takes, as set by resizing it in Notion.
+    pub fn width_ratio(&self) -> Option<f64> {
+        self.column.width_ratio
     }
 }
 
-impl MarkdownBlock for Column {
-    fn to_markdown(&self) -> String {
-        self.children.to_markdown(self.meta.depth)
+impl WriteMarkdown for Column {
+    fn write_markdown(
+        &self,
+        out: &mut dyn fmt::Write,
+        _context: RenderContext,
+        options: &MarkdownRenderOptions,
+    ) -> fmt::Result {
+        write_blocks(&self.children, out, options)
     }
 }
 
@@ -55,8 +69,8 @@ mod test {
             item.to_markdown() + "\n",
             indoc! {r#"
                 this is paragraph
-                this is paragraph
 
+                this is paragraph
             "#}
         )
     }
@@ -1,32 +1,120 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
-use super::{Block, BlockChildren, BlockMeta, MarkdownBlock};
+use std::fmt;
 
-#[derive(Deserialize, Clone, Debug)]
+use crate::rich_text::RichTextVec;
+
+use super::{
+    Block, BlockEnvelope, ColumnLayout, MarkdownRenderOptions, RenderContext, WriteMarkdown,
+    column::Column, write_blocks,
+};
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "snake_case")]
 pub struct ColumnList {
-    #[serde(skip_serializing, default)]
-    children: Vec<Block>,
-    #[serde(skip_serializing, default)]
-    meta: BlockMeta,
+    #[serde(flatten)]
+    pub(crate) envelope: BlockEnvelope,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) children: Vec<Block>,
 }
 
 impl ColumnList {
     pub(crate) fn append(&mut self, child: Block) {
         self.children.push(child);
     }
+}
+
+impl ColumnList {
+    fn columns(&self) -> impl Iterator<Item = &Column> {
+        self.children.iter().filter_map(|child| match child {
+            Block::Column(column) => Some(column),
+            _ => None,
+        })
+    }
+
+    fn write_html_flex(
+        &self,
+        out: &mut dyn fmt::Write,
+        options: &MarkdownRenderOptions,
+    ) -> fmt::Result {
+        out.write_str("<div style=\"display: flex; gap: 1em;\">\n")?;
+
+        for column in self.columns() {
+            let flex = column.width_ratio().unwrap_or(1.0);
+            write!(out, "<div style=\"flex: {};\">\n\n", flex)?;
+            if !column.children.is_empty() {
+                write_blocks(&column.children, out, options)?;
+                out.write_char('\n')?;
+            }
+            writeln!(out, "</div>")?;
+        }
+
+        write!(out, "</div>")
+    }
+
+    /// Whether [`ColumnLayout::Table`] can lay the columns out as a table, which needs at
+    /// least one column and nothing but paragraphs in every column.
+    pub fn fits_table(&self) -> bool {
+        self.columns().next().is_some()
+            && self.columns().all(|column| {
+                column
+                    .children
+                    .iter()
+                    .all(|child| matches!(child, Block::Paragraph(_)))
+            })
+    }
 
-    pub(crate) fn with_meta(self, meta: BlockMeta) -> ColumnList {
-        ColumnList {
-            meta,
-            children: self.children,
+    /// Renders the columns as the cells of a single-row table, or `None` when a column holds
+    /// anything but paragraphs.
+    fn to_table(&self, options: &MarkdownRenderOptions) -> Option<String> {
+        let cells = self
+            .columns()
+            .map(|column| {
+                column
+                    .children
+                    .iter()
+                    .map(|child| match child {
+                        Block::Paragraph(paragraph) => Some(
+                            paragraph
+                                .paragraph
+                                .rich_text
+                                .to_table_cell_markdown_with_options(options),
+                        ),
+                        _ => None,
+                    })
+                    .collect::<Option<Vec<String>>>()
+                    .map(|paragraphs| paragraphs.join("<br><br>"))
+            })
+            .collect::<Option<Vec<String>>>()?;
+
+        if cells.is_empty() {
+            return None;
         }
+
+        Some(format!(
+            "|{}\n|{}\n| {} |",
+            " |".repeat(cells.len()),
+            " --- |".repeat(cells.len()),
+            cells.join(" | ")
+        ))
     }
 }
 
-impl MarkdownBlock for ColumnList {
-    fn to_markdown(&self) -> String {
-        self.children.to_markdown(self.meta.depth)
+impl WriteMarkdown for ColumnList {
+    fn write_markdown(
+        &self,
+        out: &mut dyn fmt::Write,
+        _context: RenderContext,
+        options: &MarkdownRenderOptions,
+    ) -> fmt::Result {
+        match options.column_layout {
+            ColumnLayout::Sequential => write_blocks(&self.children, out, options),
+            ColumnLayout::HtmlFlex => self.write_html_flex(out, options),
+            ColumnLayout::Table => match self.to_table(options) {
+                Some(table) => out.write_str(&table),
+                None => write_blocks(&self.children, out, options),
+            },
+        }
     }
 }
 
@@ -35,8 +123,9 @@ mod test {
 
     use indoc::indoc;
     use pretty_assertions::assert_eq;
+    use serde_json::json;
 
-    use crate::block::Block;
+    use crate::block::{Block, ColumnLayout, MarkdownRenderOptions};
 
     #[test]
     fn test_to_markdown() {
@@ -61,8 +150,143 @@ mod test {
                 this is paragraph
 
                 this is paragraph
+            "#}
+        )
+    }
 
+    fn column_list() -> Block {
+        let mut item: Block =
+            serde_json::from_str(include_str!("../tests/block/column_list_response.json")).unwrap();
+
+        let mut column1: Block =
+            serde_json::from_str(include_str!("../tests/block/column_response.json")).unwrap();
+        let mut column2 = column1.clone();
+
+        let child: Block =
+            serde_json::from_str(include_str!("../tests/block/paragraph_response.json")).unwrap();
+
+        column1.append(child.clone());
+        column1.append(child.clone());
+        column2.append(child);
+        item.append(column1);
+        item.append(column2);
+        item
+    }
+
+    #[test]
+    fn test_to_markdown_with_html_flex_layout() {
+        let options = MarkdownRenderOptions {
+            column_layout: ColumnLayout::HtmlFlex,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            column_list().to_markdown_with_options(&options) + "\n",
+            indoc! {r#"
+                <div style="display: flex; gap: 1em;">
+                <div style="flex: 0.5;">
 
+                this is paragraph
+
+                this is paragraph
+                </div>
+                <div style="flex: 0.5;">
+
+                this is paragraph
+                </div>
+                </div>
+            "#}
+        )
+    }
+
+    #[test]
+    fn test_to_markdown_with_table_layout() {
+        let options = MarkdownRenderOptions {
+            column_layout: ColumnLayout::Table,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            column_list().to_markdown_with_options(&options) + "\n",
+            indoc! {r#"
+                | | |
+                | --- | --- |
+                | this is paragraph<br><br>this is paragraph | this is paragraph |
+            "#}
+        )
+    }
+
+    #[test]
+    fn test_to_markdown_with_table_layout_escapes_pipes() {
+        let mut item: Block =
+            serde_json::from_str(include_str!("../tests/block/column_list_response.json")).unwrap();
+        let mut column: Block =
+            serde_json::from_str(include_str!("../tests/block/column_response.json")).unwrap();
+        let text = |content: &str, code: bool| {
+            json!({
+                "type": "text",
+                "text": { "content": content, "link": null },
+                "plain_text": content,
+                "href": null,
+                "annotations": {
+                    "bold": false, "italic": false, "strikethrough": false,
+                    "underline": false, "code": code, "color": "default"
+                }
+            })
+        };
+        column.append(
+            serde_json::from_value(json!({
+                "type": "paragraph",
+                "id": "paragraph-id",
+                "paragraph": { "rich_text": [text("a|b or ", false), text("c|d", true)] }
+            }))
+            .unwrap(),
+        );
+        item.append(column);
+
+        let options = MarkdownRenderOptions {
+            column_layout: ColumnLayout::Table,
+            ..Default::default()
+        };
+        assert_eq!(
+            item.to_markdown_with_options(&options) + "\n",
+            indoc! {r#"
+                | |
+                | --- |
+                | a\|b or `c\|d` |
+            "#}
+        )
+    }
+
+    #[test]
+    fn test_to_markdown_with_table_layout_falls_back() {
+        let mut item: Block =
+            serde_json::from_str(include_str!("../tests/block/column_list_response.json")).unwrap();
+        let mut column: Block =
+            serde_json::from_str(include_str!("../tests/block/column_response.json")).unwrap();
+        let child: Block =
+            serde_json::from_str(include_str!("../tests/block/divider_response.json")).unwrap();
+
+        column.append(child);
+        item.append(column);
+        let Block::ColumnList(columns) = &item else {
+            unreachable!()
+        };
+        assert!(!columns.fits_table());
+        let Block::ColumnList(columns) = column_list() else {
+            unreachable!()
+        };
+        assert!(columns.fits_table());
+
+        let options = MarkdownRenderOptions {
+            column_layout: ColumnLayout::Table,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            item.to_markdown_with_options(&options) + "\n",
+            indoc! {r#"
+                -----
             "#}
         )
     }
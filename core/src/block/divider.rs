@@ -1,13 +1,16 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
-use super::MarkdownBlock;
+use super::{BlockEnvelope, MarkdownBlock, MarkdownRenderOptions};
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "snake_case")]
-pub struct Divider {}
+pub struct Divider {
+    #[serde(flatten)]
+    pub(crate) envelope: BlockEnvelope,
+}
 
 impl MarkdownBlock for Divider {
-    fn to_markdown(&self) -> String {
+    fn to_markdown(&self, _options: &MarkdownRenderOptions) -> String {
         "-----".to_string()
     }
 }
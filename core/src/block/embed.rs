@@ -1,20 +1,22 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
-use super::MarkdownBlock;
+use super::{BlockEnvelope, MarkdownBlock, MarkdownRenderOptions};
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "snake_case")]
 pub struct Embed {
+    #[serde(flatten)]
+    pub(crate) envelope: BlockEnvelope,
     embed: EmbedContent,
 }
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 
 struct EmbedContent {
     url: String,
 }
 
 impl MarkdownBlock for Embed {
-    fn to_markdown(&self) -> String {
+    fn to_markdown(&self, _options: &MarkdownRenderOptions) -> String {
         format!("[Embed: {}]({})", self.embed.url, self.embed.url)
     }
 }
@@ -1,21 +1,28 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
-use super::MarkdownBlock;
+use super::{BlockEnvelope, MarkdownBlock, MarkdownRenderOptions, MathDelimiters};
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct Equation {
+    #[serde(flatten)]
+    pub(crate) envelope: BlockEnvelope,
     equation: EquationContent,
 }
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "snake_case")]
 pub struct EquationContent {
     expression: String,
 }
 
 impl MarkdownBlock for Equation {
-    fn to_markdown(&self) -> String {
-        format!("$$\n{}\n$$", self.equation.expression)
+    fn to_markdown(&self, options: &MarkdownRenderOptions) -> String {
+        let (open, close) = match options.math_delimiters {
+            MathDelimiters::Dollars => ("$$", "$$"),
+            MathDelimiters::Brackets => ("\\[", "\\]"),
+            MathDelimiters::EscapedBrackets => ("\\\\[", "\\\\]"),
+        };
+        format!("{}\n{}\n{}", open, self.equation.expression, close)
     }
 }
 
@@ -25,7 +32,7 @@ mod test {
     use indoc::indoc;
     use pretty_assertions::assert_eq;
 
-    use crate::block::Block;
+    use crate::block::{Block, MarkdownRenderOptions, MathDelimiters};
 
     #[test]
     fn test_to_markdown() {
@@ -41,4 +48,33 @@ mod test {
             "#}
         )
     }
+
+    #[test]
+    fn test_to_markdown_with_brackets() {
+        let item: Block =
+            serde_json::from_str(include_str!("../tests/block/equation_response.json")).unwrap();
+        let render = |math_delimiters| {
+            item.to_markdown_with_options(&MarkdownRenderOptions {
+                math_delimiters,
+                ..Default::default()
+            }) + "\n"
+        };
+
+        assert_eq!(
+            render(MathDelimiters::Brackets),
+            indoc! {r#"
+                \[
+                x + y = 1 \\ x^2 + y^1 = 1
+                \]
+            "#}
+        );
+        assert_eq!(
+            render(MathDelimiters::EscapedBrackets),
+            indoc! {r#"
+                \\[
+                x + y = 1 \\ x^2 + y^1 = 1
+                \\]
+            "#}
+        );
+    }
 }
@@ -1,26 +1,28 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
-use super::MarkdownBlock;
+use super::{BlockEnvelope, MarkdownBlock, MarkdownRenderOptions};
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "snake_case")]
 pub struct File {
+    #[serde(flatten)]
+    pub(crate) envelope: BlockEnvelope,
     file: FileContent,
 }
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 
 struct FileContent {
     file: FileUrl,
     name: String,
 }
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 
 struct FileUrl {
     url: String,
 }
 
 impl MarkdownBlock for File {
-    fn to_markdown(&self) -> String {
+    fn to_markdown(&self, _options: &MarkdownRenderOptions) -> String {
         format!("[{}]({})", self.file.name, self.file.file.url)
     }
 }
@@ -1,42 +1,51 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
-use crate::{block::BlockChildren, rich_text::RichTextVec};
+use std::fmt;
 
-use super::{Block, BlockContent, BlockMeta, MarkdownBlock};
+use crate::rich_text::RichTextVec;
 
-#[derive(Deserialize, Clone, Debug)]
+use super::{
+    Block, BlockContent, BlockEnvelope, MarkdownRenderOptions, RenderContext, WriteMarkdown,
+    write_blocks,
+};
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "snake_case")]
 pub struct Heading1 {
+    #[serde(flatten)]
+    pub(crate) envelope: BlockEnvelope,
     pub(crate) heading_1: BlockContent,
-    #[serde(skip_serializing, default)]
-    children: Vec<Block>,
-    #[serde(skip_serializing, default)]
-    meta: BlockMeta,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) children: Vec<Block>,
 }
 
 impl Heading1 {
     pub(crate) fn append(&mut self, child: Block) {
         self.children.push(child);
     }
-
-    pub(crate) fn with_meta(self, meta: BlockMeta) -> Heading1 {
-        Heading1 {
-            meta,
-            children: self.children,
-            heading_1: self.heading_1,
-        }
-    }
 }
 
-impl MarkdownBlock for Heading1 {
-    fn to_markdown(&self) -> String {
-        let children = self.children.to_markdown(self.meta.depth + 1);
-
-        if children.is_empty() {
-            format!("# {}", self.heading_1.rich_text.to_markdown())
-        } else {
-            format!("# {}\n{}", self.heading_1.rich_text.to_markdown(), children)
+impl WriteMarkdown for Heading1 {
+    fn write_markdown(
+        &self,
+        out: &mut dyn fmt::Write,
+        _context: RenderContext,
+        options: &MarkdownRenderOptions,
+    ) -> fmt::Result {
+        write!(
+            out,
+            "# {}{}",
+            self.envelope.anchor(options),
+            self.heading_1
+                .rich_text
+                .to_inline_markdown_with_options(options)
+        )?;
+
+        if !self.children.is_empty() {
+            out.write_str("\n\n")?;
+            write_blocks(&self.children, out, options)?;
         }
+        Ok(())
     }
 }
 
@@ -76,9 +85,10 @@ mod test {
             item.to_markdown() + "\n",
             indoc! {r#"
                 # this is headline1
-                  this is paragraph
-                  this is paragraph
 
+                this is paragraph
+
+                this is paragraph
             "#}
         )
     }
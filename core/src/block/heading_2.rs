@@ -1,46 +1,51 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+
+use std::fmt;
 
 use crate::rich_text::RichTextVec;
 
-use super::{Block, BlockChildren, BlockContent, BlockMeta, MarkdownBlock};
+use super::{
+    Block, BlockContent, BlockEnvelope, MarkdownRenderOptions, RenderContext, WriteMarkdown,
+    write_blocks,
+};
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "snake_case")]
 pub struct Heading2 {
+    #[serde(flatten)]
+    pub(crate) envelope: BlockEnvelope,
     pub(crate) heading_2: BlockContent,
-    #[serde(skip_serializing, default)]
-    children: Vec<Block>,
-    #[serde(skip_serializing, default)]
-    meta: BlockMeta,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) children: Vec<Block>,
 }
 
 impl Heading2 {
     pub(crate) fn append(&mut self, child: Block) {
         self.children.push(child);
     }
-
-    pub(crate) fn with_meta(self, meta: BlockMeta) -> Heading2 {
-        Heading2 {
-            meta,
-            children: self.children,
-            heading_2: self.heading_2,
-        }
-    }
 }
 
-impl MarkdownBlock for Heading2 {
-    fn to_markdown(&self) -> String {
-        let children = self.children.to_markdown(self.meta.depth + 1);
-
-        if children.is_empty() {
-            format!("## {}", self.heading_2.rich_text.to_markdown())
-        } else {
-            format!(
-                "## {}\n{}",
-                self.heading_2.rich_text.to_markdown(),
-                children
-            )
+impl WriteMarkdown for Heading2 {
+    fn write_markdown(
+        &self,
+        out: &mut dyn fmt::Write,
+        _context: RenderContext,
+        options: &MarkdownRenderOptions,
+    ) -> fmt::Result {
+        write!(
+            out,
+            "## {}{}",
+            self.envelope.anchor(options),
+            self.heading_2
+                .rich_text
+                .to_inline_markdown_with_options(options)
+        )?;
+
+        if !self.children.is_empty() {
+            out.write_str("\n\n")?;
+            write_blocks(&self.children, out, options)?;
         }
+        Ok(())
     }
 }
 
@@ -80,9 +85,10 @@ mod test {
             item.to_markdown() + "\n",
             indoc! {r#"
                 ## this is headline2
-                  this is paragraph
-                  this is paragraph
 
+                this is paragraph
+
+                this is paragraph
             "#}
         )
     }
@@ -1,44 +1,48 @@
-use super::{Block, BlockChildren, BlockContent, BlockMeta, MarkdownBlock};
+use super::{
+    Block, BlockContent, BlockEnvelope, MarkdownRenderOptions, RenderContext, WriteMarkdown,
+    write_blocks,
+};
 use crate::rich_text::RichTextVec;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use std::fmt;
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "snake_case")]
 pub struct Heading3 {
+    #[serde(flatten)]
+    pub(crate) envelope: BlockEnvelope,
     pub(crate) heading_3: BlockContent,
-    #[serde(skip_serializing, default)]
-    children: Vec<Block>,
-    #[serde(skip_serializing, default)]
-    meta: BlockMeta,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) children: Vec<Block>,
 }
 
 impl Heading3 {
     pub(crate) fn append(&mut self, child: Block) {
         self.children.push(child);
     }
-
-    pub(crate) fn with_meta(self, meta: BlockMeta) -> Heading3 {
-        Heading3 {
-            meta,
-            children: self.children,
-            heading_3: self.heading_3,
-        }
-    }
 }
 
-impl MarkdownBlock for Heading3 {
-    fn to_markdown(&self) -> String {
-        let children = self.children.to_markdown(self.meta.depth + 1);
+impl WriteMarkdown for Heading3 {
+    fn write_markdown(
+        &self,
+        out: &mut dyn fmt::Write,
+        _context: RenderContext,
+        options: &MarkdownRenderOptions,
+    ) -> fmt::Result {
+        write!(
+            out,
+            "### {}{}",
+            self.envelope.anchor(options),
+            self.heading_3
+                .rich_text
+                .to_inline_markdown_with_options(options)
+        )?;
 
-        if children.is_empty() {
-            format!("### {}", self.heading_3.rich_text.to_markdown())
-        } else {
-            format!(
-                "### {}\n{}",
-                self.heading_3.rich_text.to_markdown(),
-                children
-            )
+        if !self.children.is_empty() {
+            out.write_str("\n\n")?;
+            write_blocks(&self.children, out, options)?;
         }
+        Ok(())
     }
 }
 
@@ -78,9 +82,10 @@ mod test {
             item.to_markdown() + "\n",
             indoc! {r#"
                 ### this is headline3
-                  this is paragraph
-                  this is paragraph
 
+                this is paragraph
+
+                this is paragraph
             "#}
         )
     }
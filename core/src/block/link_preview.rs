@@ -1,20 +1,22 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
-use super::MarkdownBlock;
+use super::{BlockEnvelope, MarkdownBlock, MarkdownRenderOptions};
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "snake_case")]
 pub struct LinkPreview {
+    #[serde(flatten)]
+    pub(crate) envelope: BlockEnvelope,
     link_preview: LinkPreviewContent,
 }
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 struct LinkPreviewContent {
     url: String,
 }
 
 impl MarkdownBlock for LinkPreview {
-    fn to_markdown(&self) -> String {
+    fn to_markdown(&self, _options: &MarkdownRenderOptions) -> String {
         format!(
             "[Preview: {}]({})",
             self.link_preview.url, self.link_preview.url
@@ -1,31 +1,79 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
-use crate::block::NOTION_ORIGIN;
+use crate::{block::NOTION_ORIGIN, rich_text::escape_markdown};
 
-use super::MarkdownBlock;
+use super::{BlockEnvelope, MarkdownBlock, MarkdownRenderOptions};
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "snake_case")]
 pub struct LinkToPage {
+    #[serde(flatten)]
+    pub(crate) envelope: BlockEnvelope,
     link_to_page: LinkToPageContent,
+    /// Title of the target page or database, when the client looked it up.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    target_title: Option<LinkTargetTitle>,
 }
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "snake_case", tag = "type")]
 enum LinkToPageContent {
     DatabaseId { database_id: String },
     PageId { page_id: String },
 }
 
-impl MarkdownBlock for LinkToPage {
-    fn to_markdown(&self) -> String {
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum LinkTargetTitle {
+    Title(String),
+    /// The integration has no access to the target, so its title is unknown.
+    Inaccessible,
+}
+
+impl LinkToPage {
+    /// ID of the page or database the block links to.
+    pub fn target_id(&self) -> &str {
         match &self.link_to_page {
-            LinkToPageContent::DatabaseId { database_id } => {
-                format!("<{}/{}>", NOTION_ORIGIN, database_id)
+            LinkToPageContent::DatabaseId { database_id } => database_id,
+            LinkToPageContent::PageId { page_id } => page_id,
+        }
+    }
+
+    pub fn is_database(&self) -> bool {
+        matches!(self.link_to_page, LinkToPageContent::DatabaseId { .. })
+    }
+
+    pub fn set_target_title(&mut self, title: LinkTargetTitle) {
+        self.target_title = Some(title);
+    }
+}
+
+impl MarkdownBlock for LinkToPage {
+    fn to_markdown(&self, options: &MarkdownRenderOptions) -> String {
+        let id = self.target_id();
+        let local_path = options.local_page_path(id);
+        let link = local_path
+            .clone()
+            .unwrap_or_else(|| format!("{}/{}", NOTION_ORIGIN, id));
+
+        match (&self.target_title, local_path) {
+            (Some(LinkTargetTitle::Title(title)), _) => {
+                format!("[{}]({})", escape_markdown(title), link)
             }
-            LinkToPageContent::PageId { page_id } => {
-                format!("<{}/{}>", NOTION_ORIGIN, page_id)
+            (Some(LinkTargetTitle::Inaccessible), _) => {
+                format!("[{}]({})", options.inaccessible_link_placeholder, link)
             }
+            (None, Some(path)) => {
+                let title = options
+                    .page_registry
+                    .as_ref()
+                    .and_then(|registry| registry.title(id))
+                    .filter(|title| !title.is_empty())
+                    .map(escape_markdown)
+                    .unwrap_or_else(|| options.inaccessible_link_placeholder.clone());
+                format!("[{}]({})", title, path)
+            }
+            (None, None) => format!("<{}>", link),
         }
     }
 }
@@ -36,7 +84,20 @@ mod test {
     use indoc::indoc;
     use pretty_assertions::assert_eq;
 
-    use crate::block::Block;
+    use std::sync::Arc;
+
+    use crate::PageRegistry;
+    use crate::block::{Block, MarkdownRenderOptions};
+
+    use super::{LinkTargetTitle, LinkToPage};
+
+    fn link_to_page(title: LinkTargetTitle) -> Block {
+        let mut item: LinkToPage =
+            serde_json::from_str(include_str!("../tests/block/link_to_page_response.json"))
+                .unwrap();
+        item.set_target_title(title);
+        Block::LinkToPage(item)
+    }
 
     #[test]
     fn test_to_markdown() {
@@ -51,4 +112,63 @@ mod test {
             "#}
         )
     }
+
+    #[test]
+    fn test_to_markdown_with_registered_page() {
+        let item: Block =
+            serde_json::from_str(include_str!("../tests/block/link_to_page_response.json"))
+                .unwrap();
+        let mut registry = PageRegistry::new();
+        registry.insert(
+            "XXXXXXXX-XXXX-XXXX-XXXX-XXXXXXXXXXXX",
+            "docs/Target.md",
+            "Target",
+        );
+
+        assert_eq!(
+            item.to_markdown_with_options(&MarkdownRenderOptions {
+                page_registry: Some(Arc::new(registry)),
+                current_page_path: Some("docs/Source.md".into()),
+                ..Default::default()
+            }),
+            "[Target](Target.md)"
+        )
+    }
+
+    #[test]
+    fn test_to_markdown_with_title() {
+        let item = link_to_page(LinkTargetTitle::Title("Tasks".into()));
+
+        assert_eq!(
+            item.to_markdown(),
+            "[Tasks](https://www.notion.so/XXXXXXXX-XXXX-XXXX-XXXX-XXXXXXXXXXXX)"
+        );
+
+        let mut registry = PageRegistry::new();
+        registry.insert("XXXXXXXX-XXXX-XXXX-XXXX-XXXXXXXXXXXX", "Tasks.md", "Tasks");
+        assert_eq!(
+            item.to_markdown_with_options(&MarkdownRenderOptions {
+                page_registry: Some(Arc::new(registry)),
+                ..Default::default()
+            }),
+            "[Tasks](Tasks.md)"
+        );
+    }
+
+    #[test]
+    fn test_to_markdown_when_inaccessible() {
+        let item = link_to_page(LinkTargetTitle::Inaccessible);
+
+        assert_eq!(
+            item.to_markdown(),
+            "[Restricted page](https://www.notion.so/XXXXXXXX-XXXX-XXXX-XXXX-XXXXXXXXXXXX)"
+        );
+        assert_eq!(
+            item.to_markdown_with_options(&MarkdownRenderOptions {
+                inaccessible_link_placeholder: "(private)".into(),
+                ..Default::default()
+            }),
+            "[(private)](https://www.notion.so/XXXXXXXX-XXXX-XXXX-XXXX-XXXXXXXXXXXX)"
+        );
+    }
 }
@@ -1,43 +1,53 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 use crate::rich_text::RichTextVec;
 
-use super::{Block, BlockChildren, BlockContent, BlockMeta, MarkdownBlock};
+use std::fmt;
 
-#[derive(Deserialize, Clone, Debug)]
+use super::{
+    Block, BlockContent, BlockEnvelope, MarkdownRenderOptions, RenderContext, WriteMarkdown,
+    list_item_children_separator, write_indented,
+};
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "snake_case")]
 pub struct NumberedListItem {
+    #[serde(flatten)]
+    pub(crate) envelope: BlockEnvelope,
     numbered_list_item: BlockContent,
-    #[serde(skip_serializing, default)]
-    children: Vec<Block>,
-    #[serde(skip_serializing, default)]
-    meta: BlockMeta,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) children: Vec<Block>,
 }
 
 impl NumberedListItem {
     pub(crate) fn append(&mut self, child: Block) {
         self.children.push(child);
     }
-
-    pub(crate) fn with_meta(self, meta: BlockMeta) -> NumberedListItem {
-        NumberedListItem {
-            meta,
-            children: self.children,
-            numbered_list_item: self.numbered_list_item,
-        }
-    }
 }
 
-impl MarkdownBlock for NumberedListItem {
-    fn to_markdown(&self) -> String {
-        let inline = self.numbered_list_item.rich_text.to_markdown();
+impl WriteMarkdown for NumberedListItem {
+    fn write_markdown(
+        &self,
+        out: &mut dyn fmt::Write,
+        context: RenderContext,
+        options: &MarkdownRenderOptions,
+    ) -> fmt::Result {
+        let marker = format!("{}. ", context.order);
+        write!(
+            out,
+            "{}{}{}",
+            marker,
+            self.envelope.anchor(options),
+            self.numbered_list_item
+                .rich_text
+                .to_markdown_with_options(options)
+        )?;
 
-        if self.children.is_empty() {
-            format!("{}. {}", self.meta.order, inline)
-        } else {
-            let children_markdown = self.children.to_markdown(self.meta.depth + 1);
-            format!("{}. {}\n{}", self.meta.order, inline, children_markdown)
+        if !self.children.is_empty() {
+            out.write_str(list_item_children_separator(&self.children))?;
+            write_indented(&self.children, out, marker.len(), options)?;
         }
+        Ok(())
     }
 }
 
@@ -46,7 +56,7 @@ mod test {
     use indoc::indoc;
     use pretty_assertions::assert_eq;
 
-    use crate::block::Block;
+    use crate::block::{Block, BlockChildren};
 
     #[test]
     fn test_to_markdown() {
@@ -65,12 +75,118 @@ mod test {
         assert_eq!(
             item.to_markdown() + "\n",
             indoc! {r#"
-              1. this is numbered list item
                 1. this is numbered list item
-                  1. this is numbered list item
+                   1. this is numbered list item
+                      1. this is numbered list item
+                   2. this is numbered list item
+            "#}
+        )
+    }
+
+    #[test]
+    fn test_numbering_restarts_after_other_blocks() {
+        let item: Block = serde_json::from_str(include_str!(
+            "../tests/block/numbered_list_item_response.json"
+        ))
+        .unwrap();
+        let paragraph: Block =
+            serde_json::from_str(include_str!("../tests/block/paragraph_response.json")).unwrap();
+        let mut item_with_child = item.clone();
+        item_with_child.append(paragraph.clone());
+
+        let mut blocks = vec![paragraph.clone(); 3];
+        blocks.extend([item.clone(), item_with_child, paragraph, item]);
+
+        assert_eq!(
+            blocks.to_markdown(0),
+            indoc! {r#"
+                this is paragraph
+
+                this is paragraph
+
+                this is paragraph
+
+                1. this is numbered list item
+                2. this is numbered list item
+
+                   this is paragraph
+
+                this is paragraph
+
+                1. this is numbered list item
+            "#}
+        )
+    }
+
+    #[test]
+    fn test_children_line_up_after_other_blocks() {
+        let item: Block = serde_json::from_str(include_str!(
+            "../tests/block/numbered_list_item_response.json"
+        ))
+        .unwrap();
+        let divider: Block =
+            serde_json::from_str(include_str!("../tests/block/divider_response.json")).unwrap();
+        let mut item_with_child = item.clone();
+        item_with_child.append(item.clone());
+
+        let mut blocks = vec![divider; 9];
+        blocks.extend([item, item_with_child]);
+
+        assert!(blocks.to_markdown(0).ends_with(indoc! {r#"
+            -----
+
+            1. this is numbered list item
+            2. this is numbered list item
+               1. this is numbered list item
+        "#}));
+    }
+
+    #[test]
+    fn test_children_line_up_with_item_text() {
+        let item: Block = serde_json::from_str(include_str!(
+            "../tests/block/numbered_list_item_response.json"
+        ))
+        .unwrap();
+        let code: Block =
+            serde_json::from_str(include_str!("../tests/block/code_response.json")).unwrap();
+        let bullet: Block = serde_json::from_str(include_str!(
+            "../tests/block/bulleted_list_item_response.json"
+        ))
+        .unwrap();
+        let mut nested = bullet.clone();
+        nested.append(code.clone());
+
+        let mut items = vec![item; 10];
+        items[0].append(code.clone());
+        items[9].append(nested);
+        items[9].append(code);
+
+        assert_eq!(
+            items.to_markdown(0),
+            indoc! {r#"
+                1. this is numbered list item
 
+                   ```markdown
+                   this is markdown code
+                   ```
                 2. this is numbered list item
+                3. this is numbered list item
+                4. this is numbered list item
+                5. this is numbered list item
+                6. this is numbered list item
+                7. this is numbered list item
+                8. this is numbered list item
+                9. this is numbered list item
+                10. this is numbered list item
+                    - this is bulleted list item
+
+                      ```markdown
+                      this is markdown code
+                      ```
 
+                    ```markdown
+                    this is markdown code
+                    ```
             "#}
         )
     }
@@ -1,18 +1,20 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 use crate::rich_text::RichTextVec;
 
-use super::{BlockContent, MarkdownBlock};
+use super::{BlockContent, BlockEnvelope, MarkdownBlock, MarkdownRenderOptions};
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "snake_case")]
 pub struct Paragraph {
-    paragraph: BlockContent,
+    #[serde(flatten)]
+    pub(crate) envelope: BlockEnvelope,
+    pub(crate) paragraph: BlockContent,
 }
 
 impl MarkdownBlock for Paragraph {
-    fn to_markdown(&self) -> String {
-        self.paragraph.rich_text.to_markdown()
+    fn to_markdown(&self, options: &MarkdownRenderOptions) -> String {
+        self.envelope.anchor(options) + &self.paragraph.rich_text.to_markdown_with_options(options)
     }
 }
 
@@ -21,8 +23,14 @@ mod test {
 
     use indoc::indoc;
     use pretty_assertions::assert_eq;
+    use rstest::rstest;
 
-    use crate::block::Block;
+    use std::sync::Arc;
+
+    use serde_json::{Value, json};
+
+    use crate::block::{Block, MarkdownRenderOptions};
+    use crate::{PageRegistry, User, UserDirectory};
 
     #[test]
     fn test_to_markdown() {
@@ -36,4 +44,195 @@ mod test {
             "#}
         )
     }
+
+    #[rstest]
+    #[case::emphasis("2 * 3 = 6_0", r"2 \* 3 = 6\_0")]
+    #[case::link_and_html("[not a link](x) <b>", r"\[not a link\](x) \<b>")]
+    #[case::code_and_math("`a` ~b~ $c$ d|e", r"\`a\` \~b\~ \$c\$ d\|e")]
+    #[case::entity("AT&T &amp; &#42;", r"AT&T \&amp; \&#42;")]
+    #[case::heading("# C# #", r"\# C# \#")]
+    #[case::list("- item", r"\- item")]
+    #[case::numbered_list("1. item", r"1\. item")]
+    #[case::quote("> quote", r"\> quote")]
+    #[case::next_line("a\n+ b\n  2) c", "a\n\\+ b\n  2\\) c")]
+    #[case::backslash(r"C:\path", r"C:\\path")]
+    fn test_to_markdown_escapes(#[case] text: &str, #[case] expected: &str) {
+        let item: Block = serde_json::from_value(json!({
+            "type": "paragraph",
+            "id": "paragraph-id",
+            "paragraph": {
+                "rich_text": [{
+                    "type": "text",
+                    "text": { "content": text, "link": null },
+                    "plain_text": text,
+                    "href": null
+                }]
+            }
+        }))
+        .unwrap();
+
+        assert_eq!(item.to_markdown(), expected);
+    }
+
+    #[test]
+    fn test_to_markdown_resolves_page_links() {
+        let item: Block = serde_json::from_value(json!({
+            "type": "paragraph",
+            "id": "paragraph-id",
+            "paragraph": {
+                "rich_text": [
+                    {
+                        "type": "mention",
+                        "mention": {
+                            "type": "page",
+                            "page": { "id": "0123abcd-0123-abcd-0123-abcd0123abcd" }
+                        },
+                        "plain_text": "Other",
+                        "href": "https://www.notion.so/0123abcd0123abcd0123abcd0123abcd",
+                        "annotations": {
+                            "bold": false, "italic": false, "strikethrough": false,
+                            "underline": false, "code": false, "color": "default"
+                        }
+                    },
+                    {
+                        "type": "text",
+                        "text": {
+                            "content": " and ",
+                            "link": null
+                        },
+                        "plain_text": " and ",
+                        "href": null,
+                        "annotations": {
+                            "bold": false, "italic": false, "strikethrough": false,
+                            "underline": false, "code": false, "color": "default"
+                        }
+                    },
+                    {
+                        "type": "text",
+                        "text": {
+                            "content": "elsewhere",
+                            "link": { "url": "https://www.notion.so/Elsewhere-ffffffffffffffffffffffffffffffff" }
+                        },
+                        "plain_text": "elsewhere",
+                        "href": "https://www.notion.so/Elsewhere-ffffffffffffffffffffffffffffffff",
+                        "annotations": {
+                            "bold": false, "italic": false, "strikethrough": false,
+                            "underline": false, "code": false, "color": "default"
+                        }
+                    }
+                ]
+            }
+        }))
+        .unwrap();
+        let mut registry = PageRegistry::new();
+        registry.insert("0123abcd0123abcd0123abcd0123abcd", "Other.md", "Other");
+
+        assert_eq!(
+            item.to_markdown_with_options(&MarkdownRenderOptions {
+                page_registry: Some(Arc::new(registry)),
+                current_page_path: Some("Page.md".into()),
+                ..Default::default()
+            }),
+            "[Other](Other.md) and [elsewhere](https://www.notion.so/Elsewhere-ffffffffffffffffffffffffffffffff)"
+        )
+    }
+
+    fn user_mention(user: Value) -> Value {
+        json!({
+            "type": "mention",
+            "mention": { "type": "user", "user": user },
+            "plain_text": "@someone",
+            "href": null,
+            "annotations": {
+                "bold": false, "italic": false, "strikethrough": false,
+                "underline": false, "code": false, "color": "default"
+            }
+        })
+    }
+
+    fn separator() -> Value {
+        json!({
+            "type": "text",
+            "text": { "content": ", ", "link": null },
+            "plain_text": ", ",
+            "href": null
+        })
+    }
+
+    #[rstest]
+    #[case::default(None, "Ada Lovelace, Unknown user, Guest")]
+    #[case::name(Some("@{name}"), "@Ada King, Unknown user, @Guest")]
+    #[case::email(
+        Some("[@{name}](mailto:{email})"),
+        "[@Ada King](mailto:ada@example.com), Unknown user, Guest"
+    )]
+    #[case::handle(
+        Some("[@{handle}](https://github.com/{handle})"),
+        "[@ada](https://github.com/ada), Unknown user, Guest"
+    )]
+    fn test_to_markdown_user_mentions(#[case] template: Option<&str>, #[case] expected: &str) {
+        let item: Block = serde_json::from_value(json!({
+            "type": "paragraph",
+            "id": "paragraph-id",
+            "paragraph": {
+                "rich_text": [
+                    user_mention(json!({
+                        "object": "user",
+                        "id": "0123abcd-0123-abcd-0123-abcd0123abcd",
+                        "name": "Ada Lovelace",
+                        "avatar_url": null,
+                        "type": "person",
+                        "person": { "email": "ada@example.com" }
+                    })),
+                    separator(),
+                    user_mention(json!({ "object": "user", "id": "removed-user" })),
+                    separator(),
+                    user_mention(json!({ "object": "user", "id": "guest", "name": "Guest" })),
+                ]
+            }
+        }))
+        .unwrap();
+
+        let options = match template {
+            Some(template) => {
+                let mut users = UserDirectory::new();
+                users.insert(User {
+                    id: "0123abcd0123abcd0123abcd0123abcd".into(),
+                    name: Some("Ada King".into()),
+                    email: Some("ada@example.com".into()),
+                });
+                users.insert_handle("ada@example.com", "ada");
+                MarkdownRenderOptions {
+                    users: Some(Arc::new(users)),
+                    user_mention_template: template.into(),
+                    ..Default::default()
+                }
+            }
+            None => MarkdownRenderOptions::default(),
+        };
+
+        assert_eq!(item.to_markdown_with_options(&options), expected);
+    }
+
+    #[test]
+    fn test_to_markdown_user_mention_fields_are_not_substituted() {
+        let item: Block = serde_json::from_value(json!({
+            "type": "paragraph",
+            "id": "paragraph-id",
+            "paragraph": {
+                "rich_text": [
+                    user_mention(json!({ "object": "user", "id": "guest", "name": "{id}" })),
+                ]
+            }
+        }))
+        .unwrap();
+
+        assert_eq!(
+            item.to_markdown_with_options(&MarkdownRenderOptions {
+                user_mention_template: "@{name} ({id}, {unknown})".into(),
+                ..Default::default()
+            }),
+            "@{id} (guest, {unknown})"
+        );
+    }
 }
@@ -1,25 +1,27 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
-use super::MarkdownBlock;
+use super::{BlockEnvelope, MarkdownBlock, MarkdownRenderOptions};
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "snake_case")]
 pub struct Pdf {
+    #[serde(flatten)]
+    pub(crate) envelope: BlockEnvelope,
     pdf: FileContent,
 }
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 
 struct FileContent {
     file: FileUrl,
 }
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 
 struct FileUrl {
     url: String,
 }
 
 impl MarkdownBlock for Pdf {
-    fn to_markdown(&self) -> String {
+    fn to_markdown(&self, _options: &MarkdownRenderOptions) -> String {
         format!(
             "[PDF Document: {}]({})",
             self.pdf.file.url, self.pdf.file.url
@@ -1,18 +1,23 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 use crate::rich_text::RichTextVec;
 
-use super::{BlockContent, MarkdownBlock};
+use super::{BlockContent, BlockEnvelope, MarkdownBlock, MarkdownRenderOptions};
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "snake_case")]
 pub struct Quote {
+    #[serde(flatten)]
+    pub(crate) envelope: BlockEnvelope,
     quote: BlockContent,
 }
 
 impl MarkdownBlock for Quote {
-    fn to_markdown(&self) -> String {
-        format!("> {}", self.quote.rich_text.to_markdown())
+    fn to_markdown(&self, options: &MarkdownRenderOptions) -> String {
+        format!(
+            "> {}",
+            self.envelope.anchor(options) + &self.quote.rich_text.to_markdown_with_options(options)
+        )
     }
 }
 
@@ -45,11 +50,11 @@ mod test {
             serde_json::from_str(include_str!("../tests/block/paragraph_response.json")).unwrap();
 
         assert_eq!(
-            vec![quote, paragraph].to_markdown(0) + "\n",
+            vec![quote, paragraph].to_markdown(0),
             indoc! {r#"
                 > this is quote
-                this is paragraph
 
+                this is paragraph
             "#}
         )
     }
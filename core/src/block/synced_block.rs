@@ -1,14 +1,31 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
-use super::{Block, BlockChildren, BlockMeta, MarkdownBlock};
+use std::fmt;
 
-#[derive(Deserialize, Clone, Debug)]
+use super::{
+    Block, BlockEnvelope, MarkdownRenderOptions, RenderContext, WriteMarkdown, html_comment,
+    write_blocks,
+};
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "snake_case")]
 pub struct SyncedBlock {
-    #[serde(skip_serializing, default)]
-    children: Vec<Block>,
-    #[serde(skip_serializing, default)]
-    meta: BlockMeta,
+    #[serde(flatten)]
+    pub(crate) envelope: BlockEnvelope,
+    synced_block: SyncedBlockContent,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) children: Vec<Block>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+struct SyncedBlockContent {
+    synced_from: Option<SyncedFrom>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "snake_case", tag = "type")]
+enum SyncedFrom {
+    BlockId { block_id: String },
 }
 
 impl SyncedBlock {
@@ -16,17 +33,43 @@ impl SyncedBlock {
         self.children.push(child);
     }
 
-    pub(crate) fn with_meta(self, meta: BlockMeta) -> SyncedBlock {
-        SyncedBlock {
-            meta,
-            children: self.children,
+    /// ID of the original block when this is a copy; its children hold the shared content.
+    pub fn synced_from(&self) -> Option<&str> {
+        match &self.synced_block.synced_from {
+            Some(SyncedFrom::BlockId { block_id }) => Some(block_id),
+            None => None,
         }
     }
+
+    fn source_id(&self) -> &str {
+        self.synced_from().unwrap_or(&self.envelope.id)
+    }
 }
 
-impl MarkdownBlock for SyncedBlock {
-    fn to_markdown(&self) -> String {
-        self.children.to_markdown(self.meta.depth)
+impl WriteMarkdown for SyncedBlock {
+    fn write_markdown(
+        &self,
+        out: &mut dyn fmt::Write,
+        _context: RenderContext,
+        options: &MarkdownRenderOptions,
+    ) -> fmt::Result {
+        if !options.synced_block_markers {
+            return write_blocks(&self.children, out, options);
+        }
+
+        out.write_str(&html_comment(&format!(
+            "synced block: {}",
+            self.source_id()
+        )))?;
+        if !self.children.is_empty() {
+            out.write_char('\n')?;
+            write_blocks(&self.children, out, options)?;
+        }
+        write!(
+            out,
+            "\n{}",
+            html_comment(&format!("/synced block: {}", self.source_id()))
+        )
     }
 }
 
@@ -36,7 +79,7 @@ mod test {
     use indoc::indoc;
     use pretty_assertions::assert_eq;
 
-    use crate::block::Block;
+    use crate::block::{Block, BlockChildren, MarkdownRenderOptions};
 
     #[test]
     fn test_to_markdown() {
@@ -55,8 +98,51 @@ mod test {
             item.to_markdown() + "\n",
             indoc! {r#"
                 this is paragraph
+
                 this is paragraph
+            "#}
+        )
+    }
 
+    #[test]
+    fn test_synced_from() {
+        let item: Block =
+            serde_json::from_str(include_str!("../tests/block/synced_block_response.json"))
+                .unwrap();
+
+        match item {
+            Block::SyncedBlock(item) => assert_eq!(
+                item.synced_from(),
+                Some("XXXXXXXX-XXXX-XXXX-XXXX-XXXXXXXXXXXX")
+            ),
+            _ => panic!("expected synced block"),
+        }
+    }
+
+    #[test]
+    fn test_to_markdown_with_markers() {
+        let mut item: Block =
+            serde_json::from_str(include_str!("../tests/block/synced_block_response.json"))
+                .unwrap();
+        let child: Block =
+            serde_json::from_str(include_str!("../tests/block/paragraph_response.json")).unwrap();
+
+        item.append(child.clone());
+        item.append(child);
+
+        let options = MarkdownRenderOptions {
+            synced_block_markers: true,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            vec![item].to_markdown_with_options(0, &options),
+            indoc! {r#"
+                <!-- synced block: XXXXXXXX-XXXX-XXXX-XXXX-XXXXXXXXXXXX -->
+                this is paragraph
+
+                this is paragraph
+                <!-- /synced block: XXXXXXXX-XXXX-XXXX-XXXX-XXXXXXXXXXXX -->
             "#}
         )
     }
@@ -1,27 +1,32 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use std::fmt;
 
 use crate::{
-    block::INDENT,
     rich_text::{RichText, RichTextVec},
+    width::display_width,
 };
 
-use super::{Block, BlockMeta, MarkdownBlock};
+use super::{
+    Block, BlockEnvelope, ColumnAlignment, MarkdownRenderOptions, RenderContext, WriteMarkdown,
+};
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct Table {
-    #[serde(skip_serializing, default)]
-    children: Vec<Block>,
-    #[serde(skip_serializing, default)]
-    meta: BlockMeta,
+    #[serde(flatten)]
+    pub(crate) envelope: BlockEnvelope,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) children: Vec<Block>,
 }
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "snake_case")]
 pub struct TableRow {
+    #[serde(flatten)]
+    pub(crate) envelope: BlockEnvelope,
     table_row: TableRowContent,
 }
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "snake_case")]
 struct TableRowContent {
     pub cells: Vec<Vec<RichText>>,
@@ -31,89 +36,253 @@ impl Table {
     pub(crate) fn append(&mut self, child: Block) {
         self.children.push(child);
     }
+}
 
-    pub(crate) fn with_meta(self, meta: BlockMeta) -> Table {
-        Table {
-            meta,
-            children: self.children,
-        }
+/// A rendered cell with its visible text, which excludes Markdown syntax.
+#[derive(Clone, Default)]
+struct Cell {
+    markdown: String,
+    text: String,
+    width: usize,
+}
+
+impl TableRow {
+    fn cells(&self, options: &MarkdownRenderOptions) -> Vec<Cell> {
+        self.table_row
+            .cells
+            .iter()
+            .map(|cell| {
+                let text: String = cell.iter().map(RichText::plain_text).collect();
+                Cell {
+                    markdown: cell.to_table_cell_markdown_with_options(options),
+                    width: display_width(&text),
+                    text,
+                }
+            })
+            .collect()
     }
 }
 
-impl MarkdownBlock for Table {
-    fn to_markdown(&self) -> String {
-        let table: Vec<Vec<String>> = self
+impl WriteMarkdown for Table {
+    fn write_markdown(
+        &self,
+        out: &mut dyn fmt::Write,
+        _context: RenderContext,
+        options: &MarkdownRenderOptions,
+    ) -> fmt::Result {
+        let table: Vec<Vec<Cell>> = self
             .children
             .iter()
             .filter_map(|child| match child {
-                Block::TableRow(table_row) => Some(
-                    table_row
-                        .table_row
-                        .cells
-                        .iter()
-                        .map(|x| x.to_markdown())
-                        .collect::<Vec<String>>(),
-                ),
+                Block::TableRow(table_row) => Some(table_row.cells(options)),
                 _ => None,
             })
             .collect();
+        let overrides = options
+            .table_alignment(&self.envelope.id)
+            .unwrap_or_default();
 
-        if table.is_empty() {
-            return String::new();
-        }
+        write_table(out, table, overrides, options)
+    }
+}
+
+/// A row found outside a table is rendered as a table of its own, so its cells are kept.
+impl WriteMarkdown for TableRow {
+    fn write_markdown(
+        &self,
+        out: &mut dyn fmt::Write,
+        _context: RenderContext,
+        options: &MarkdownRenderOptions,
+    ) -> fmt::Result {
+        write_table(out, vec![self.cells(options)], &[], options)
+    }
+}
+
+/// Lays `table` out with the first row as the header. Rows shorter than the longest one are
+/// padded with empty cells, and unless the tables are compact, cells are padded to the width
+/// of their column.
+///
+/// Columns are aligned as `overrides` says, and those past its end as inferred from their
+/// cells when the options ask for it.
+fn write_table(
+    out: &mut dyn fmt::Write,
+    mut table: Vec<Vec<Cell>>,
+    overrides: &[ColumnAlignment],
+    options: &MarkdownRenderOptions,
+) -> fmt::Result {
+    let Some(columns_count) = table.iter().map(Vec::len).max() else {
+        return Ok(());
+    };
+    for row in &mut table {
+        row.resize(columns_count, Cell::default());
+    }
 
-        let columns_count = table[0].len();
-        let mut columns_widths = vec![0; columns_count];
+    let compact = options.compact_tables;
+    let mut columns_widths = vec![3; columns_count];
+    if !compact {
         for row in &table {
-            for (i, cell) in row.iter().enumerate().take(columns_count) {
-                let w: usize = cell.chars().map(|c| if c.is_ascii() { 1 } else { 2 }).sum();
-                columns_widths[i] = columns_widths[i].max(w);
+            for (width, cell) in columns_widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.width);
             }
         }
+    }
 
-        let pad_cell = |cell: &str, target: usize| {
-            let cw: usize = cell.chars().map(|c| if c.is_ascii() { 1 } else { 2 }).sum();
-            let padding = target.saturating_sub(cw);
-            format!("{}{}", cell, " ".repeat(padding))
-        };
-
-        let format_row = |row: &[String]| {
-            let cells: Vec<String> = row
-                .iter()
-                .enumerate()
-                .map(|(i, cell)| pad_cell(cell, columns_widths[i]))
-                .collect();
-            format!("| {} |", cells.join(" | "))
-        };
-
-        let mut markdown = String::new();
-        markdown.push_str(&format_row(&table[0]));
-        markdown.push('\n');
+    let alignments: Vec<ColumnAlignment> = (0..columns_count)
+        .map(|column| match overrides.get(column) {
+            Some(&alignment) => alignment,
+            None if options.infer_table_alignment => infer_alignment(&table, column),
+            None => ColumnAlignment::Default,
+        })
+        .collect();
 
-        let separators: Vec<String> = columns_widths
+    let format_row = |row: &[Cell]| {
+        let cells: Vec<String> = row
             .iter()
-            .map(|&w| "-".repeat(w.max(3)))
+            .zip(&columns_widths)
+            .zip(&alignments)
+            .map(|((cell, &width), alignment)| {
+                let padding = if compact {
+                    0
+                } else {
+                    width.saturating_sub(cell.width)
+                };
+                let (before, after) = match alignment {
+                    ColumnAlignment::Right => (padding, 0),
+                    ColumnAlignment::Center => (padding / 2, padding - padding / 2),
+                    ColumnAlignment::Default | ColumnAlignment::Left => (0, padding),
+                };
+                format!(
+                    "{}{}{}",
+                    " ".repeat(before),
+                    cell.markdown,
+                    " ".repeat(after)
+                )
+            })
             .collect();
-        markdown.push_str(&format!("| {} |\n", separators.join(" | ")));
+        format!("| {} |", cells.join(" | "))
+    };
 
-        for row in &table[1..] {
-            let mut cells = row.clone();
-            cells.resize(columns_count, String::new());
-            markdown.push_str(&format_row(&cells));
-            markdown.push('\n');
-        }
+    let mut rows = table.iter();
+    let mut lines = Vec::with_capacity(table.len() + 1);
+    if let Some(header) = rows.next() {
+        lines.push(format_row(header));
+    }
+
+    let separators: Vec<String> = columns_widths
+        .iter()
+        .zip(&alignments)
+        .map(|(&width, alignment)| match alignment {
+            ColumnAlignment::Default => "-".repeat(width),
+            ColumnAlignment::Left => format!(":{}", "-".repeat(width - 1)),
+            ColumnAlignment::Center => format!(":{}:", "-".repeat(width - 2)),
+            ColumnAlignment::Right => format!("{}:", "-".repeat(width - 1)),
+        })
+        .collect();
+    lines.push(format!("| {} |", separators.join(" | ")));
+    lines.extend(rows.map(|row| format_row(row)));
 
-        format!("{}{}", INDENT.repeat(self.meta.depth), markdown)
+    out.write_str(&lines.join("\n"))
+}
+
+/// Right-aligns a column whose cells below the header are all numbers, amounts or dates,
+/// ignoring empty ones.
+fn infer_alignment(table: &[Vec<Cell>], column: usize) -> ColumnAlignment {
+    let mut cells = table
+        .iter()
+        .skip(1)
+        .map(|row| row[column].text.trim())
+        .filter(|text| !text.is_empty())
+        .peekable();
+
+    if cells.peek().is_some() && cells.all(is_numeric) {
+        ColumnAlignment::Right
+    } else {
+        ColumnAlignment::Default
     }
 }
 
+/// Whether `text` is a number, possibly signed, grouped, in parentheses, a percentage or an
+/// amount of money, or a date or time written with digits, such as `2024-05-17` or `17.05.2024`.
+fn is_numeric(text: &str) -> bool {
+    const CURRENCY_SYMBOLS: &[char] = &['$', '€', '£', '¥', '₩', '₹', '₽', '¢', '₿'];
+
+    let mut text = text.trim();
+    if let Some(inner) = text.strip_prefix('(').and_then(|t| t.strip_suffix(')')) {
+        text = inner.trim();
+    }
+    text = text.trim_start_matches(['+', '-', '−']);
+    text = text.trim_start_matches(CURRENCY_SYMBOLS).trim_start();
+    text = text.trim_end_matches(['%', '‰']);
+    text = text.trim_end_matches(CURRENCY_SYMBOLS).trim_end();
+
+    // ISO 4217 codes, as in `USD 12` or `12 EUR`.
+    let is_code = |code: &str| code.len() == 3 && code.chars().all(|c| c.is_ascii_uppercase());
+    if let Some((code, rest)) = text.split_once(' ')
+        && is_code(code)
+    {
+        text = rest.trim_start();
+    }
+    if let Some((rest, code)) = text.rsplit_once(' ')
+        && is_code(code)
+    {
+        text = rest.trim_end();
+    }
+    text = text.strip_suffix('Z').unwrap_or(text);
+
+    let chars: Vec<char> = text.chars().collect();
+    let (Some(first), Some(last)) = (chars.first(), chars.last()) else {
+        return false;
+    };
+    first.is_ascii_digit()
+        && last.is_ascii_digit()
+        && chars.iter().enumerate().all(|(i, &c)| match c {
+            '0'..='9' | ',' | '.' | '\'' | '_' | '-' | '/' | ':' => true,
+            // Spaces group digits, and `T` separates the date and time of ISO 8601.
+            ' ' | '\u{a0}' | '\u{202f}' | 'T' => {
+                chars[i - 1].is_ascii_digit() && chars[i + 1].is_ascii_digit()
+            }
+            _ => false,
+        })
+}
+
 #[cfg(test)]
 mod test {
 
+    use std::collections::HashMap;
+
     use indoc::indoc;
     use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use super::is_numeric;
+    use crate::block::{Block, ColumnAlignment, MarkdownRenderOptions};
+
+    /// A row whose cells hold `text`, bold where the flag is set.
+    fn row(cells: &[(&str, bool)]) -> Block {
+        let mut row: serde_json::Value =
+            serde_json::from_str(include_str!("../tests/block/table_row_response.json")).unwrap();
+        let template = row["table_row"]["cells"][0][0].clone();
+        row["table_row"]["cells"] = cells
+            .iter()
+            .map(|&(text, bold)| {
+                let mut cell = template.clone();
+                cell["text"]["content"] = text.into();
+                cell["plain_text"] = text.into();
+                cell["annotations"]["bold"] = bold.into();
+                serde_json::json!([cell])
+            })
+            .collect();
+        serde_json::from_value(row).unwrap()
+    }
 
-    use crate::block::Block;
+    fn table(rows: Vec<Block>) -> Block {
+        let mut table: Block =
+            serde_json::from_str(include_str!("../tests/block/table_response.json")).unwrap();
+        for row in rows {
+            table.append(row);
+        }
+        table
+    }
 
     #[test]
     fn test_to_markdown() {
@@ -128,7 +297,7 @@ mod test {
         table.append(row2);
 
         assert_eq!(
-            table.to_markdown(),
+            table.to_markdown() + "\n",
             indoc! {r#"
                 | this  | is  | table row |
                 | ----- | --- | --------- |
@@ -136,4 +305,219 @@ mod test {
             "#}
         )
     }
+
+    #[test]
+    fn test_rows_longer_than_header() {
+        let mut table: Block =
+            serde_json::from_str(include_str!("../tests/block/table_response.json")).unwrap();
+        let row: serde_json::Value =
+            serde_json::from_str(include_str!("../tests/block/table_row_response.json")).unwrap();
+        let mut long_row = row.clone();
+        let extra_cell = long_row["table_row"]["cells"][0].clone();
+        long_row["table_row"]["cells"]
+            .as_array_mut()
+            .unwrap()
+            .push(extra_cell);
+
+        table.append(serde_json::from_value(row).unwrap());
+        table.append(serde_json::from_value(long_row).unwrap());
+
+        assert_eq!(
+            table.to_markdown() + "\n",
+            indoc! {r#"
+                | this  | is  | table row |       |
+                | ----- | --- | --------- | ----- |
+                | this  | is  | table row | this  |
+            "#}
+        )
+    }
+
+    #[test]
+    fn test_row_outside_table() {
+        let row: Block =
+            serde_json::from_str(include_str!("../tests/block/table_row_response.json")).unwrap();
+
+        assert_eq!(
+            row.to_markdown() + "\n",
+            indoc! {r#"
+                | this  | is  | table row |
+                | ----- | --- | --------- |
+            "#}
+        )
+    }
+
+    #[test]
+    fn test_widths_of_visible_text() {
+        let table = table(vec![
+            row(&[("name", false), ("note", false)]),
+            row(&[("café", true), ("日本", false)]),
+            row(&[("e\u{301}te\u{301}", false), ("👩‍💻", false)]),
+        ]);
+
+        assert_eq!(
+            table.to_markdown() + "\n",
+            indoc! {"
+                | name | note |
+                | ---- | ---- |
+                | **café** | 日本 |
+                | e\u{301}te\u{301}  | 👩‍💻   |
+            "}
+        )
+    }
+
+    #[test]
+    fn test_pipes_and_line_breaks_in_cells() {
+        let text = |content: &str, code: bool| {
+            serde_json::json!([{
+                "type": "text",
+                "text": { "content": content, "link": null },
+                "plain_text": content,
+                "href": null,
+                "annotations": {
+                    "bold": false, "italic": false, "strikethrough": false,
+                    "underline": false, "code": code, "color": "default"
+                }
+            }])
+        };
+        let row: Block = serde_json::from_value(serde_json::json!({
+            "type": "table_row",
+            "id": "row-id",
+            "table_row": {
+                "cells": [text("a|b", false), text("a|b", true), text("line1\nline2", false)]
+            }
+        }))
+        .unwrap();
+
+        assert_eq!(
+            table(vec![row]).to_markdown() + "\n",
+            indoc! {r#"
+                | a\|b | `a\|b` | line1<br>line2 |
+                | --- | --- | ----------- |
+            "#}
+        )
+    }
+
+    #[test]
+    fn test_compact_tables() {
+        let table = table(vec![
+            row(&[("name", false), ("note", false)]),
+            row(&[("longer name", true), ("", false)]),
+        ]);
+        let options = MarkdownRenderOptions {
+            compact_tables: true,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            table.to_markdown_with_options(&options) + "\n",
+            indoc! {r#"
+                | name | note |
+                | --- | --- |
+                | **longer name** |  |
+            "#}
+        )
+    }
+
+    fn price_list() -> Block {
+        table(vec![
+            row(&[("item", false), ("price", false), ("updated", false)]),
+            row(&[
+                ("apples", false),
+                ("$1,200.50", false),
+                ("2024-05-17", false),
+            ]),
+            row(&[("pears", false), ("", false), ("17.05.2024", false)]),
+            row(&[("plums", false), ("(30)", true), ("n/a", false)]),
+        ])
+    }
+
+    #[test]
+    fn test_infer_alignment() {
+        let options = MarkdownRenderOptions {
+            infer_table_alignment: true,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            price_list().to_markdown_with_options(&options) + "\n",
+            indoc! {r#"
+                | item   |     price | updated    |
+                | ------ | --------: | ---------- |
+                | apples | \$1,200.50 | 2024-05-17 |
+                | pears  |           | 17.05.2024 |
+                | plums  |      **(30)** | n/a        |
+            "#}
+        )
+    }
+
+    #[test]
+    fn test_alignment_overrides() {
+        let options = MarkdownRenderOptions {
+            infer_table_alignment: true,
+            table_alignments: HashMap::from([(
+                "xxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxx".into(),
+                vec![ColumnAlignment::Center, ColumnAlignment::Default],
+            )]),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            price_list().to_markdown_with_options(&options) + "\n",
+            indoc! {r#"
+                |  item  | price     | updated    |
+                | :----: | --------- | ---------- |
+                | apples | \$1,200.50 | 2024-05-17 |
+                | pears  |           | 17.05.2024 |
+                | plums  | **(30)**      | n/a        |
+            "#}
+        )
+    }
+
+    #[test]
+    fn test_compact_alignment() {
+        let options = MarkdownRenderOptions {
+            compact_tables: true,
+            table_alignments: HashMap::from([(
+                "XXXXXXXX-XXXX-XXXX-XXXX-XXXXXXXXXXXX".into(),
+                vec![
+                    ColumnAlignment::Left,
+                    ColumnAlignment::Right,
+                    ColumnAlignment::Center,
+                ],
+            )]),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            price_list()
+                .to_markdown_with_options(&options)
+                .lines()
+                .nth(1),
+            Some("| :-- | --: | :-: |")
+        )
+    }
+
+    #[rstest]
+    #[case("42", true)]
+    #[case("-3.5", true)]
+    #[case("1,234,567.89", true)]
+    #[case("1 000", true)]
+    #[case("12%", true)]
+    #[case("€ 9,99", true)]
+    #[case("USD 100", true)]
+    #[case("100 EUR", true)]
+    #[case("(250)", true)]
+    #[case("2024-05-17", true)]
+    #[case("05/17/2024", true)]
+    #[case("2024-05-17T10:30:00Z", true)]
+    #[case("", false)]
+    #[case("v1.2", false)]
+    #[case("1.2.3-beta", false)]
+    #[case("12 apples", false)]
+    #[case("$", false)]
+    #[case("- 5", true)]
+    #[case("5 -", false)]
+    fn test_is_numeric(#[case] text: &str, #[case] numeric: bool) {
+        assert_eq!(is_numeric(text), numeric);
+    }
 }
@@ -1,17 +1,22 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 use crate::rich_text::RichTextVec;
 
-use super::{INDENT, MarkdownBlock, heading_1::Heading1, heading_2::Heading2, heading_3::Heading3};
+use super::{
+    BlockEnvelope, INDENT, MarkdownBlock, MarkdownRenderOptions, heading_1::Heading1,
+    heading_2::Heading2, heading_3::Heading3,
+};
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "snake_case")]
 pub struct TableOfContents {
-    #[serde(skip_serializing, default)]
+    #[serde(flatten)]
+    pub(crate) envelope: BlockEnvelope,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     headings: Vec<Heading>,
 }
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 enum Heading {
     Heading1(Heading1),
     Heading2(Heading2),
@@ -19,7 +24,7 @@ enum Heading {
 }
 
 impl MarkdownBlock for TableOfContents {
-    fn to_markdown(&self) -> String {
+    fn to_markdown(&self, options: &MarkdownRenderOptions) -> String {
         self.headings
             .iter()
             .map(|heading| match heading {
@@ -27,24 +32,24 @@ impl MarkdownBlock for TableOfContents {
                     format!(
                         "{}- [{}](#{})",
                         INDENT.repeat(0),
-                        item.heading_1.rich_text.to_markdown(),
-                        item.heading_1.rich_text.to_markdown()
+                        item.heading_1.rich_text.to_markdown_with_options(options),
+                        item.heading_1.rich_text.to_markdown_with_options(options)
                     )
                 }
                 Heading::Heading2(item) => {
                     format!(
                         "{}- [{}](#{})",
-                        INDENT.repeat(1),
-                        item.heading_2.rich_text.to_markdown(),
-                        item.heading_2.rich_text.to_markdown()
+                        INDENT,
+                        item.heading_2.rich_text.to_markdown_with_options(options),
+                        item.heading_2.rich_text.to_markdown_with_options(options)
                     )
                 }
                 Heading::Heading3(item) => {
                     format!(
                         "{}- [{}](#{})",
                         INDENT.repeat(2),
-                        item.heading_3.rich_text.to_markdown(),
-                        item.heading_3.rich_text.to_markdown()
+                        item.heading_3.rich_text.to_markdown_with_options(options),
+                        item.heading_3.rich_text.to_markdown_with_options(options)
                     )
                 }
             })
@@ -59,7 +64,7 @@ mod test {
     use indoc::indoc;
     use pretty_assertions::assert_eq;
 
-    use crate::block::MarkdownBlock;
+    use crate::block::{MarkdownBlock, MarkdownRenderOptions};
     use crate::{
         heading_1::Heading1,
         heading_2::Heading2,
@@ -88,7 +93,7 @@ mod test {
         item.headings.push(Heading::Heading2(heading2));
 
         assert_eq!(
-            item.to_markdown() + "\n",
+            item.to_markdown(&MarkdownRenderOptions::default()) + "\n",
             indoc! {r#"
                     - [this is headline1](#this is headline1)
                       - [this is headline2](#this is headline2)
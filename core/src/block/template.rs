@@ -1,13 +1,16 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
-use super::{MarkdownBlock, TEMPLATE_NODE_TEXT};
+use super::{BlockEnvelope, MarkdownBlock, MarkdownRenderOptions, TEMPLATE_NODE_TEXT};
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "snake_case")]
-pub struct Template {}
+pub struct Template {
+    #[serde(flatten)]
+    pub(crate) envelope: BlockEnvelope,
+}
 
 impl MarkdownBlock for Template {
-    fn to_markdown(&self) -> String {
+    fn to_markdown(&self, _options: &MarkdownRenderOptions) -> String {
         TEMPLATE_NODE_TEXT.into()
     }
 }
@@ -1,22 +1,24 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
-use crate::{
-    block::BlockChildren,
-    rich_text::{RichText, RichTextVec},
-};
+use std::fmt;
+
+use crate::rich_text::{RichText, RichTextVec};
 
-use super::{Block, BlockMeta, MarkdownBlock};
+use super::{
+    Block, BlockEnvelope, MarkdownRenderOptions, RenderContext, WriteMarkdown,
+    list_item_children_separator, write_indented,
+};
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct ToDo {
+    #[serde(flatten)]
+    pub(crate) envelope: BlockEnvelope,
     to_do: ToDoContent,
-    #[serde(skip_serializing, default)]
-    children: Vec<Block>,
-    #[serde(skip_serializing, default)]
-    meta: BlockMeta,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) children: Vec<Block>,
 }
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "snake_case")]
 pub struct ToDoContent {
     rich_text: Vec<RichText>,
@@ -27,31 +29,32 @@ impl ToDo {
     pub(crate) fn append(&mut self, child: Block) {
         self.children.push(child);
     }
-
-    pub(crate) fn with_meta(self, meta: BlockMeta) -> ToDo {
-        ToDo {
-            meta,
-            children: self.children,
-            to_do: self.to_do,
-        }
-    }
 }
 
-impl MarkdownBlock for ToDo {
-    fn to_markdown(&self) -> String {
+impl WriteMarkdown for ToDo {
+    fn write_markdown(
+        &self,
+        out: &mut dyn fmt::Write,
+        _context: RenderContext,
+        options: &MarkdownRenderOptions,
+    ) -> fmt::Result {
+        // The checkbox is part of the item's text, so its content lines up after the bullet.
+        let marker = "- ";
         let checked_x = if self.to_do.checked { "x" } else { " " };
+        write!(
+            out,
+            "{}[{}] {}{}",
+            marker,
+            checked_x,
+            self.envelope.anchor(options),
+            self.to_do.rich_text.to_markdown_with_options(options)
+        )?;
 
-        if self.children.is_empty() {
-            format!("- [{}] {}", checked_x, self.to_do.rich_text.to_markdown())
-        } else {
-            let children_markdown = self.children.to_markdown(self.meta.depth + 1);
-            format!(
-                "- [{}] {}\n{}",
-                checked_x,
-                self.to_do.rich_text.to_markdown(),
-                children_markdown
-            )
+        if !self.children.is_empty() {
+            out.write_str(list_item_children_separator(&self.children))?;
+            write_indented(&self.children, out, marker.len(), options)?;
         }
+        Ok(())
     }
 }
 
@@ -82,7 +85,6 @@ mod test {
                 - [ ] this is to do item
                   - [ ] this is to do item
                   - [x] this is to do item
-
             "#}
         )
     }
@@ -1,40 +1,49 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 use crate::rich_text::RichTextVec;
 
-use super::{Block, BlockChildren, BlockContent, BlockMeta, MarkdownBlock};
+use std::fmt;
 
-#[derive(Deserialize, Clone, Debug)]
+use super::{
+    Block, BlockContent, BlockEnvelope, MarkdownRenderOptions, RenderContext, WriteMarkdown,
+    write_blocks,
+};
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "snake_case")]
 pub struct Toggle {
+    #[serde(flatten)]
+    pub(crate) envelope: BlockEnvelope,
     toggle: BlockContent,
-    #[serde(skip_serializing, default)]
-    children: Vec<Block>,
-    #[serde(skip_serializing, default)]
-    meta: BlockMeta,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) children: Vec<Block>,
 }
 
 impl Toggle {
     pub(crate) fn append(&mut self, child: Block) {
         self.children.push(child);
     }
-
-    pub(crate) fn with_meta(self, meta: BlockMeta) -> Toggle {
-        Toggle {
-            meta,
-            toggle: self.toggle,
-            children: self.children,
-        }
-    }
 }
 
-impl MarkdownBlock for Toggle {
-    fn to_markdown(&self) -> String {
-        format!(
-            "{}\n{}",
-            self.toggle.rich_text.to_markdown(),
-            self.children.to_markdown(self.meta.depth + 1)
-        )
+impl WriteMarkdown for Toggle {
+    fn write_markdown(
+        &self,
+        out: &mut dyn fmt::Write,
+        _context: RenderContext,
+        options: &MarkdownRenderOptions,
+    ) -> fmt::Result {
+        write!(
+            out,
+            "{}{}",
+            self.envelope.anchor(options),
+            self.toggle.rich_text.to_markdown_with_options(options)
+        )?;
+
+        if !self.children.is_empty() {
+            out.write_str("\n\n")?;
+            write_blocks(&self.children, out, options)?;
+        }
+        Ok(())
     }
 }
 
@@ -63,9 +72,10 @@ mod test {
             item.to_markdown() + "\n",
             indoc! {r#"
                 this is toggle
-                  this is paragraph
-                  this is paragraph
 
+                this is paragraph
+
+                this is paragraph
             "#}
         )
     }
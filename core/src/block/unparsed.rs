@@ -0,0 +1,119 @@
+use serde::{Deserialize, Serialize};
+
+use std::fmt;
+
+use super::{
+    Block, MarkdownRenderOptions, RenderContext, WriteMarkdown, html_comment, write_blocks,
+};
+
+/// Block the API returned but that could not be deserialized into any other variant.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Unparsed {
+    id: Option<String>,
+    block_type: Option<String>,
+    raw: String,
+    /// Children are retrieved like those of any other block, so they are still exported.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) children: Vec<Block>,
+}
+
+impl Unparsed {
+    pub fn new(id: Option<String>, block_type: Option<String>, raw: String) -> Unparsed {
+        Unparsed {
+            id,
+            block_type,
+            raw,
+            children: Vec::new(),
+        }
+    }
+
+    pub(crate) fn append(&mut self, child: Block) {
+        self.children.push(child);
+    }
+
+    pub fn id(&self) -> Option<&str> {
+        self.id.as_deref()
+    }
+
+    pub fn block_type(&self) -> Option<&str> {
+        self.block_type.as_deref()
+    }
+
+    /// The JSON of the block exactly as the API returned it.
+    pub fn raw(&self) -> &str {
+        &self.raw
+    }
+}
+
+impl WriteMarkdown for Unparsed {
+    fn write_markdown(
+        &self,
+        out: &mut dyn fmt::Write,
+        _context: RenderContext,
+        options: &MarkdownRenderOptions,
+    ) -> fmt::Result {
+        let block_type = self.block_type.as_deref().unwrap_or("unknown");
+        let comment = match &self.id {
+            Some(id) => html_comment(&format!("unparsed {} block: {}", block_type, id)),
+            None => html_comment(&format!("unparsed {} block", block_type)),
+        };
+        out.write_str(&comment)?;
+
+        if !self.children.is_empty() {
+            out.write_str("\n\n")?;
+            write_blocks(&self.children, out, options)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    use crate::block::{Block, unparsed::Unparsed};
+
+    #[test]
+    fn test_to_markdown() {
+        let item = Block::Unparsed(Unparsed::new(
+            Some("XXXXXXXX-XXXX-XXXX-XXXX-XXXXXXXXXXXX".into()),
+            Some("image".into()),
+            include_str!("../tests/block/image_response.json").into(),
+        ));
+
+        assert_eq!(
+            item.to_markdown() + "\n",
+            indoc! {r#"
+                <!-- unparsed image block: XXXXXXXX-XXXX-XXXX-XXXX-XXXXXXXXXXXX -->
+            "#}
+        )
+    }
+
+    #[test]
+    fn test_to_markdown_without_id() {
+        let item = Block::Unparsed(Unparsed::new(None, None, "{}".into()));
+
+        assert_eq!(
+            item.to_markdown() + "\n",
+            indoc! {r#"
+                <!-- unparsed unknown block -->
+            "#}
+        )
+    }
+
+    #[test]
+    fn test_to_markdown_escapes_comment() {
+        let item = Block::Unparsed(Unparsed::new(
+            Some("id --> <b>".into()),
+            Some("odd--type".into()),
+            "{}".into(),
+        ));
+
+        assert_eq!(
+            item.to_markdown(),
+            "<!-- unparsed odd- -type block: id - -&gt; <b&gt; -->"
+        )
+    }
+}
@@ -0,0 +1,34 @@
+use std::{error, fmt};
+
+/// A block tree that Markdown cannot represent as Notion shows it. The infallible renderers
+/// render such trees anyway, as closely as they can; the `try_` ones return this instead.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RenderError {
+    /// A table row that is not a child of a table. Rendered as a table of its own.
+    TableRowOutsideTable { block_id: Option<String> },
+    /// A child of a table that is not a table row. Left out of the table.
+    BlockInsideTable {
+        table_id: String,
+        block_id: Option<String>,
+    },
+}
+
+impl fmt::Display for RenderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RenderError::TableRowOutsideTable { block_id } => write!(
+                f,
+                "table row {} is not inside a table",
+                block_id.as_deref().unwrap_or("without id")
+            ),
+            RenderError::BlockInsideTable { table_id, block_id } => write!(
+                f,
+                "block {} inside table {} is not a table row",
+                block_id.as_deref().unwrap_or("without id"),
+                table_id
+            ),
+        }
+    }
+}
+
+impl error::Error for RenderError {}
@@ -0,0 +1,797 @@
+use pulldown_cmark::{CodeBlockKind, Event, HeadingLevel, Options, Parser, Tag, TagEnd};
+use serde_json::{Value, json};
+
+/// Notion rejects rich text objects whose content is longer than this.
+const MAX_TEXT_LENGTH: usize = 2000;
+
+/// Notion rejects rich text arrays with more elements than this.
+const MAX_RICH_TEXT_ITEMS: usize = 100;
+
+/// Languages accepted by the `language` of a Notion `code` block.
+const NOTION_LANGUAGES: &[&str] = &[
+    "abap",
+    "agda",
+    "arduino",
+    "ascii art",
+    "assembly",
+    "bash",
+    "basic",
+    "bnf",
+    "c",
+    "c#",
+    "c++",
+    "clojure",
+    "coffeescript",
+    "coq",
+    "css",
+    "dart",
+    "dhall",
+    "diff",
+    "docker",
+    "ebnf",
+    "elixir",
+    "elm",
+    "erlang",
+    "f#",
+    "flow",
+    "fortran",
+    "gherkin",
+    "glsl",
+    "go",
+    "graphql",
+    "groovy",
+    "haskell",
+    "hcl",
+    "html",
+    "idris",
+    "java",
+    "javascript",
+    "json",
+    "julia",
+    "kotlin",
+    "latex",
+    "less",
+    "lisp",
+    "livescript",
+    "llvm ir",
+    "lua",
+    "makefile",
+    "markdown",
+    "markup",
+    "matlab",
+    "mathematica",
+    "mermaid",
+    "nix",
+    "notion formula",
+    "objective-c",
+    "ocaml",
+    "pascal",
+    "perl",
+    "php",
+    "plain text",
+    "powershell",
+    "prolog",
+    "protobuf",
+    "purescript",
+    "python",
+    "r",
+    "racket",
+    "reason",
+    "ruby",
+    "rust",
+    "sass",
+    "scala",
+    "scheme",
+    "scss",
+    "shell",
+    "smalltalk",
+    "solidity",
+    "sql",
+    "swift",
+    "toml",
+    "typescript",
+    "vb.net",
+    "verilog",
+    "vhdl",
+    "visual basic",
+    "webassembly",
+    "xml",
+    "yaml",
+    "java/c/c++/c#",
+];
+
+/// Parses CommonMark with GFM extensions into the block objects accepted by Notion's
+/// "append block children" endpoint.
+///
+/// Nested blocks are returned under `children`, at any depth; the API accepts at most two
+/// levels per request, so callers have to append deeper levels separately.
+///
+/// Text with more rich text objects than a block can hold continues in paragraphs after it,
+/// or nested in it for quotes and list items. Raw HTML has no Notion equivalent and is kept
+/// as code: HTML blocks become `html` code blocks and inline HTML inline code.
+pub fn markdown_to_blocks(markdown: &str) -> Vec<Value> {
+    let options = Options::ENABLE_TABLES
+        | Options::ENABLE_STRIKETHROUGH
+        | Options::ENABLE_TASKLISTS
+        | Options::ENABLE_MATH;
+
+    let mut converter = Converter::default();
+    for event in Parser::new_ext(markdown, options) {
+        converter.event(event);
+    }
+    converter.finish()
+}
+
+enum Container {
+    Root,
+    Quote,
+    List { ordered: bool },
+    Item { checked: Option<bool> },
+    Table,
+}
+
+struct Frame {
+    container: Container,
+    /// Rich text of the block itself, taken from its first paragraph.
+    rich_text: Option<Vec<Value>>,
+    children: Vec<Value>,
+}
+
+impl Frame {
+    fn new(container: Container) -> Frame {
+        Frame {
+            container,
+            rich_text: None,
+            children: Vec::new(),
+        }
+    }
+}
+
+#[derive(Default, Clone, Copy, PartialEq)]
+struct Style {
+    bold: usize,
+    italic: usize,
+    strikethrough: usize,
+}
+
+struct Converter {
+    frames: Vec<Frame>,
+    rich_text: Vec<Value>,
+    style: Style,
+    link: Option<String>,
+    /// Caption of the image being parsed, with the image URL.
+    image: Option<(String, Vec<Value>)>,
+    /// Images found inside the current paragraph, emitted after it.
+    images: Vec<Value>,
+    code: Option<(String, String)>,
+    html: Option<String>,
+    rows: Vec<Vec<Vec<Value>>>,
+}
+
+impl Default for Converter {
+    fn default() -> Converter {
+        Converter {
+            frames: vec![Frame::new(Container::Root)],
+            rich_text: Vec::new(),
+            style: Style::default(),
+            link: None,
+            image: None,
+            images: Vec::new(),
+            code: None,
+            html: None,
+            rows: Vec::new(),
+        }
+    }
+}
+
+impl Converter {
+    fn event(&mut self, event: Event) {
+        match event {
+            Event::Start(tag) => self.start(tag),
+            Event::End(tag) => self.end(tag),
+            Event::Text(text) => match &mut self.code {
+                Some((_, code)) => code.push_str(&text),
+                None => self.text(&text, false),
+            },
+            Event::Code(text) => self.text(&text, true),
+            Event::InlineMath(expression) => self.push_rich_text(json!({
+                "type": "equation",
+                "equation": { "expression": expression.as_ref() },
+            })),
+            Event::DisplayMath(expression) => {
+                self.flush_paragraph();
+                self.push_block(block(
+                    "equation",
+                    json!({ "expression": expression.trim() }),
+                ));
+            }
+            Event::Html(html) => {
+                if let Some(block) = &mut self.html {
+                    block.push_str(&html);
+                }
+            }
+            Event::InlineHtml(html) => self.text(&html, true),
+            Event::SoftBreak => self.text(" ", false),
+            Event::HardBreak => self.text("\n", false),
+            Event::Rule => self.push_block(block("divider", json!({}))),
+            Event::TaskListMarker(checked) => {
+                if let Some(Frame {
+                    container: Container::Item { checked: item },
+                    ..
+                }) = self.frames.last_mut()
+                {
+                    *item = Some(checked);
+                }
+            }
+            Event::FootnoteReference(_) => {}
+        }
+    }
+
+    fn start(&mut self, tag: Tag) {
+        match tag {
+            Tag::BlockQuote(_) => {
+                self.flush_inline();
+                self.frames.push(Frame::new(Container::Quote));
+            }
+            Tag::List(start) => {
+                self.flush_inline();
+                self.frames.push(Frame::new(Container::List {
+                    ordered: start.is_some(),
+                }));
+            }
+            Tag::Item => self
+                .frames
+                .push(Frame::new(Container::Item { checked: None })),
+            Tag::Table(_) => {
+                self.rows.clear();
+                self.frames.push(Frame::new(Container::Table));
+            }
+            Tag::TableHead | Tag::TableRow => self.rows.push(Vec::new()),
+            Tag::CodeBlock(kind) => {
+                let language = match kind {
+                    CodeBlockKind::Fenced(info) => {
+                        info.split_whitespace().next().unwrap_or("").to_string()
+                    }
+                    CodeBlockKind::Indented => String::new(),
+                };
+                self.code = Some((language, String::new()));
+            }
+            Tag::HtmlBlock => self.html = Some(String::new()),
+            Tag::Strong => self.style.bold += 1,
+            Tag::Emphasis => self.style.italic += 1,
+            Tag::Strikethrough => self.style.strikethrough += 1,
+            Tag::Link { dest_url, .. } => self.link = Some(dest_url.to_string()),
+            Tag::Image { dest_url, .. } => self.image = Some((dest_url.to_string(), Vec::new())),
+            _ => {}
+        }
+    }
+
+    fn end(&mut self, tag: TagEnd) {
+        match tag {
+            TagEnd::Paragraph => self.flush_paragraph(),
+            TagEnd::Heading(level) => {
+                let (rich_text, rest) = split_rich_text(std::mem::take(&mut self.rich_text));
+                let block_type = match level {
+                    HeadingLevel::H1 => "heading_1",
+                    HeadingLevel::H2 => "heading_2",
+                    _ => "heading_3",
+                };
+                self.push_block(block(block_type, json!({ "rich_text": rich_text })));
+                for paragraph in rest {
+                    self.push_block(paragraph);
+                }
+                self.flush_images();
+            }
+            TagEnd::BlockQuote(_) => {
+                self.flush_inline();
+                let frame = self.frames.pop().unwrap();
+                let (rich_text, mut children) =
+                    split_rich_text(frame.rich_text.unwrap_or_default());
+                children.extend(frame.children);
+                self.push_block(with_children(
+                    "quote",
+                    json!({ "rich_text": rich_text }),
+                    children,
+                ));
+            }
+            TagEnd::List(_) => {
+                let frame = self.frames.pop().unwrap();
+                for item in frame.children {
+                    self.push_block(item);
+                }
+            }
+            TagEnd::Item => {
+                self.flush_inline();
+                let frame = self.frames.pop().unwrap();
+                let Container::Item { checked } = frame.container else {
+                    return;
+                };
+                let ordered = matches!(
+                    self.frames.last().map(|f| &f.container),
+                    Some(Container::List { ordered: true })
+                );
+                let (rich_text, mut children) =
+                    split_rich_text(frame.rich_text.unwrap_or_default());
+                children.extend(frame.children);
+
+                let item = match checked {
+                    Some(checked) => with_children(
+                        "to_do",
+                        json!({ "rich_text": rich_text, "checked": checked }),
+                        children,
+                    ),
+                    None if ordered => with_children(
+                        "numbered_list_item",
+                        json!({ "rich_text": rich_text }),
+                        children,
+                    ),
+                    None => with_children(
+                        "bulleted_list_item",
+                        json!({ "rich_text": rich_text }),
+                        children,
+                    ),
+                };
+                self.frames.last_mut().unwrap().children.push(item);
+            }
+            TagEnd::TableCell => {
+                let cell = std::mem::take(&mut self.rich_text);
+                if let Some(row) = self.rows.last_mut() {
+                    row.push(cell);
+                }
+            }
+            TagEnd::Table => {
+                self.frames.pop();
+                let rows = std::mem::take(&mut self.rows);
+                let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+                let children = rows
+                    .into_iter()
+                    .map(|mut cells| {
+                        cells.resize(width, Vec::new());
+                        block("table_row", json!({ "cells": cells }))
+                    })
+                    .collect::<Vec<Value>>();
+
+                self.push_block(block(
+                    "table",
+                    json!({
+                        "table_width": width,
+                        "has_column_header": true,
+                        "has_row_header": false,
+                        "children": children,
+                    }),
+                ));
+            }
+            TagEnd::CodeBlock => {
+                if let Some((language, code)) = self.code.take() {
+                    self.push_code(notion_language(&language), &code);
+                }
+            }
+            TagEnd::HtmlBlock => {
+                if let Some(html) = self.html.take() {
+                    self.push_code("html", &html);
+                }
+            }
+            TagEnd::Strong => self.style.bold -= 1,
+            TagEnd::Emphasis => self.style.italic -= 1,
+            TagEnd::Strikethrough => self.style.strikethrough -= 1,
+            TagEnd::Link => self.link = None,
+            TagEnd::Image => {
+                if let Some((url, caption)) = self.image.take() {
+                    self.images.push(block(
+                        "image",
+                        json!({
+                            "type": "external",
+                            "external": { "url": url },
+                            "caption": caption,
+                        }),
+                    ));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn text(&mut self, text: &str, code: bool) {
+        for rich_text in text_chunks(text, self.style, self.link.as_deref(), code) {
+            self.push_rich_text(rich_text);
+        }
+    }
+
+    fn push_rich_text(&mut self, rich_text: Value) {
+        let target = match &mut self.image {
+            Some((_, caption)) => caption,
+            None => &mut self.rich_text,
+        };
+
+        // Merge with the previous run when only the content differs.
+        if let (Some(last), Some(content)) =
+            (target.last_mut(), rich_text["text"]["content"].as_str())
+            && last["type"] == "text"
+            && last["annotations"] == rich_text["annotations"]
+            && last["text"]["link"] == rich_text["text"]["link"]
+            && let Some(previous) = last["text"]["content"].as_str()
+            && previous.chars().count() + content.chars().count() <= MAX_TEXT_LENGTH
+        {
+            last["text"]["content"] = Value::String(format!("{}{}", previous, content));
+            return;
+        }
+        target.push(rich_text);
+    }
+
+    fn push_block(&mut self, block: Value) {
+        self.frames.last_mut().unwrap().children.push(block);
+    }
+
+    /// Pushes `code` as code blocks, as many as its rich text needs.
+    fn push_code(&mut self, language: &str, code: &str) {
+        let code = code.strip_suffix('\n').unwrap_or(code);
+        let rich_text = text_chunks(code, Style::default(), None, false);
+        for chunk in rich_text.chunks(MAX_RICH_TEXT_ITEMS) {
+            self.push_block(block(
+                "code",
+                json!({ "rich_text": chunk, "language": language }),
+            ));
+        }
+    }
+
+    /// Ends the paragraph being parsed. Inside a list item or quote its text becomes the
+    /// text of that block when it is the first paragraph.
+    fn flush_paragraph(&mut self) {
+        if !self.rich_text.is_empty() {
+            let rich_text = std::mem::take(&mut self.rich_text);
+            let frame = self.frames.last_mut().unwrap();
+
+            match frame.container {
+                Container::Item { .. } | Container::Quote
+                    if frame.rich_text.is_none() && frame.children.is_empty() =>
+                {
+                    frame.rich_text = Some(rich_text)
+                }
+                _ => frame.children.extend(
+                    rich_text
+                        .chunks(MAX_RICH_TEXT_ITEMS)
+                        .map(|chunk| block("paragraph", json!({ "rich_text": chunk }))),
+                ),
+            }
+        }
+        self.flush_images();
+    }
+
+    /// Ends the text that tight list items hold without a paragraph around it.
+    fn flush_inline(&mut self) {
+        if matches!(
+            self.frames.last().map(|f| &f.container),
+            Some(Container::Item { .. } | Container::Quote)
+        ) {
+            self.flush_paragraph();
+        }
+    }
+
+    fn flush_images(&mut self) {
+        for image in std::mem::take(&mut self.images) {
+            self.push_block(image);
+        }
+    }
+
+    fn finish(mut self) -> Vec<Value> {
+        self.flush_paragraph();
+        self.frames.swap_remove(0).children
+    }
+}
+
+fn block(block_type: &str, content: Value) -> Value {
+    json!({ "object": "block", "type": block_type, block_type: content })
+}
+
+fn with_children(block_type: &str, mut content: Value, children: Vec<Value>) -> Value {
+    if !children.is_empty() {
+        content["children"] = Value::Array(children);
+    }
+    block(block_type, content)
+}
+
+/// Splits `rich_text` into the part a block can hold and paragraphs holding the rest.
+fn split_rich_text(mut rich_text: Vec<Value>) -> (Vec<Value>, Vec<Value>) {
+    if rich_text.len() <= MAX_RICH_TEXT_ITEMS {
+        return (rich_text, Vec::new());
+    }
+
+    let rest = rich_text.split_off(MAX_RICH_TEXT_ITEMS);
+    let paragraphs = rest
+        .chunks(MAX_RICH_TEXT_ITEMS)
+        .map(|chunk| block("paragraph", json!({ "rich_text": chunk })))
+        .collect();
+    (rich_text, paragraphs)
+}
+
+/// Splits `text` into rich text objects no longer than Notion allows.
+fn text_chunks(text: &str, style: Style, link: Option<&str>, code: bool) -> Vec<Value> {
+    let chars: Vec<char> = text.chars().collect();
+
+    chars
+        .chunks(MAX_TEXT_LENGTH)
+        .map(|chunk| {
+            json!({
+                "type": "text",
+                "text": {
+                    "content": chunk.iter().collect::<String>(),
+                    "link": link.map(|url| json!({ "url": url })),
+                },
+                "annotations": {
+                    "bold": style.bold > 0,
+                    "italic": style.italic > 0,
+                    "strikethrough": style.strikethrough > 0,
+                    "underline": false,
+                    "code": code,
+                    "color": "default",
+                },
+            })
+        })
+        .collect()
+}
+
+/// Maps a fenced code block info string onto a language Notion accepts.
+fn notion_language(language: &str) -> &'static str {
+    let language = language.to_lowercase();
+    let language = match language.as_str() {
+        "" | "text" | "txt" | "plaintext" => "plain text",
+        "rs" => "rust",
+        "js" | "jsx" | "mjs" => "javascript",
+        "ts" | "tsx" => "typescript",
+        "py" => "python",
+        "rb" => "ruby",
+        "sh" | "zsh" | "console" => "shell",
+        "ps1" | "pwsh" => "powershell",
+        "cpp" | "cc" | "cxx" | "hpp" => "c++",
+        "cs" | "csharp" => "c#",
+        "fs" | "fsharp" => "f#",
+        "objc" | "objectivec" => "objective-c",
+        "yml" => "yaml",
+        "md" => "markdown",
+        "dockerfile" => "docker",
+        "make" => "makefile",
+        "tex" => "latex",
+        "golang" => "go",
+        "kt" => "kotlin",
+        "hs" => "haskell",
+        "ex" | "exs" => "elixir",
+        "proto" => "protobuf",
+        "wasm" => "webassembly",
+        "vb" => "visual basic",
+        "vbnet" => "vb.net",
+        "asm" => "assembly",
+        "llvm" => "llvm ir",
+        other => other,
+    };
+
+    NOTION_LANGUAGES
+        .iter()
+        .find(|known| **known == language)
+        .copied()
+        .unwrap_or("plain text")
+}
+
+#[cfg(test)]
+mod test {
+
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+    use serde_json::{Value, json};
+
+    use super::markdown_to_blocks;
+
+    fn text(content: &str) -> Value {
+        json!({
+            "type": "text",
+            "text": { "content": content, "link": null },
+            "annotations": {
+                "bold": false,
+                "italic": false,
+                "strikethrough": false,
+                "underline": false,
+                "code": false,
+                "color": "default",
+            },
+        })
+    }
+
+    #[test]
+    fn test_headings_and_paragraphs() {
+        let blocks = markdown_to_blocks(indoc! {r#"
+            # this is headline1
+            ## this is headline2
+            #### this is headline4
+
+            this is
+            paragraph
+        "#});
+
+        assert_eq!(
+            blocks,
+            vec![
+                json!({ "object": "block", "type": "heading_1", "heading_1": { "rich_text": [text("this is headline1")] } }),
+                json!({ "object": "block", "type": "heading_2", "heading_2": { "rich_text": [text("this is headline2")] } }),
+                json!({ "object": "block", "type": "heading_3", "heading_3": { "rich_text": [text("this is headline4")] } }),
+                json!({ "object": "block", "type": "paragraph", "paragraph": { "rich_text": [text("this is paragraph")] } }),
+            ]
+        )
+    }
+
+    #[test]
+    fn test_annotations_and_links() {
+        let blocks = markdown_to_blocks(
+            "**bold** _italic_ ~~strike~~ `code` [link](https://example.com) $x^2$",
+        );
+
+        let rich_text = &blocks[0]["paragraph"]["rich_text"];
+        assert_eq!(rich_text[0]["text"]["content"], "bold");
+        assert_eq!(rich_text[0]["annotations"]["bold"], true);
+        assert_eq!(rich_text[2]["annotations"]["italic"], true);
+        assert_eq!(rich_text[4]["annotations"]["strikethrough"], true);
+        assert_eq!(rich_text[6]["annotations"]["code"], true);
+        assert_eq!(rich_text[8]["text"]["link"]["url"], "https://example.com");
+        assert_eq!(rich_text[10]["equation"]["expression"], "x^2");
+    }
+
+    #[test]
+    fn test_lists() {
+        let blocks = markdown_to_blocks(indoc! {r#"
+            - this is bulleted list item
+              - this is nested item
+            1. this is numbered list item
+            - [ ] this is to do item
+            - [x] this is done item
+        "#});
+
+        assert_eq!(
+            blocks,
+            vec![
+                json!({ "object": "block", "type": "bulleted_list_item", "bulleted_list_item": {
+                    "rich_text": [text("this is bulleted list item")],
+                    "children": [
+                        { "object": "block", "type": "bulleted_list_item", "bulleted_list_item": { "rich_text": [text("this is nested item")] } }
+                    ]
+                } }),
+                json!({ "object": "block", "type": "numbered_list_item", "numbered_list_item": { "rich_text": [text("this is numbered list item")] } }),
+                json!({ "object": "block", "type": "to_do", "to_do": { "rich_text": [text("this is to do item")], "checked": false } }),
+                json!({ "object": "block", "type": "to_do", "to_do": { "rich_text": [text("this is done item")], "checked": true } }),
+            ]
+        )
+    }
+
+    #[test]
+    fn test_code_quote_equation_and_divider() {
+        let blocks = markdown_to_blocks(indoc! {r#"
+            ```rs
+            fn main() {}
+            ```
+
+            > this is quote
+
+            $$
+            x + y = 1
+            $$
+
+            ---
+        "#});
+
+        assert_eq!(
+            blocks,
+            vec![
+                json!({ "object": "block", "type": "code", "code": { "rich_text": [text("fn main() {}")], "language": "rust" } }),
+                json!({ "object": "block", "type": "quote", "quote": { "rich_text": [text("this is quote")] } }),
+                json!({ "object": "block", "type": "equation", "equation": { "expression": "x + y = 1" } }),
+                json!({ "object": "block", "type": "divider", "divider": {} }),
+            ]
+        )
+    }
+
+    #[test]
+    fn test_table() {
+        let blocks = markdown_to_blocks(indoc! {r#"
+            | this | is  |
+            | ---- | --- |
+            | table row |
+        "#});
+
+        assert_eq!(
+            blocks,
+            vec![json!({ "object": "block", "type": "table", "table": {
+                "table_width": 2,
+                "has_column_header": true,
+                "has_row_header": false,
+                "children": [
+                    { "object": "block", "type": "table_row", "table_row": { "cells": [[text("this")], [text("is")]] } },
+                    { "object": "block", "type": "table_row", "table_row": { "cells": [[text("table row")], []] } },
+                ]
+            } })]
+        )
+    }
+
+    #[test]
+    fn test_image() {
+        let blocks = markdown_to_blocks("![this is image](https://picsum.photos/200/300)");
+
+        assert_eq!(
+            blocks,
+            vec![json!({ "object": "block", "type": "image", "image": {
+                "type": "external",
+                "external": { "url": "https://picsum.photos/200/300" },
+                "caption": [text("this is image")],
+            } })]
+        )
+    }
+
+    #[test]
+    fn test_long_text_is_split() {
+        let blocks = markdown_to_blocks(&"a".repeat(2500));
+
+        let rich_text = blocks[0]["paragraph"]["rich_text"].as_array().unwrap();
+        assert_eq!(rich_text.len(), 2);
+        assert_eq!(
+            rich_text[0]["text"]["content"].as_str().unwrap().len(),
+            2000
+        );
+    }
+
+    #[test]
+    fn test_long_rich_text_is_split_across_blocks() {
+        // 150 bold words with 149 plain spaces between them, 299 rich text objects.
+        let words = vec!["**a**"; 150].join(" ");
+        let blocks = markdown_to_blocks(&format!("{}\n\n- {}\n", words, words));
+
+        let lengths: Vec<usize> = blocks
+            .iter()
+            .map(|block| {
+                let block_type = block["type"].as_str().unwrap();
+                block[block_type]["rich_text"].as_array().unwrap().len()
+            })
+            .collect();
+        assert_eq!(lengths, vec![100, 100, 99, 100]);
+        assert_eq!(blocks[3]["type"], "bulleted_list_item");
+
+        let children = blocks[3]["bulleted_list_item"]["children"]
+            .as_array()
+            .unwrap();
+        assert_eq!(children.len(), 2);
+        assert_eq!(children[0]["type"], "paragraph");
+        assert_eq!(
+            children[1]["paragraph"]["rich_text"]
+                .as_array()
+                .unwrap()
+                .len(),
+            99
+        );
+    }
+
+    #[test]
+    fn test_html_is_kept_as_code() {
+        let blocks = markdown_to_blocks(indoc! {r#"
+            <div>
+            this is html
+            </div>
+
+            this is <b>inline</b> html
+        "#});
+
+        assert_eq!(
+            blocks[0],
+            json!({ "object": "block", "type": "code", "code": {
+                "rich_text": [text("<div>\nthis is html\n</div>")],
+                "language": "html",
+            } })
+        );
+        let rich_text = &blocks[1]["paragraph"]["rich_text"];
+        assert_eq!(rich_text[1]["text"]["content"], "<b>");
+        assert_eq!(rich_text[1]["annotations"]["code"], true);
+        assert_eq!(rich_text[2]["text"]["content"], "inline");
+        assert_eq!(rich_text[2]["annotations"]["code"], false);
+    }
+
+    #[test]
+    fn test_unknown_language() {
+        let blocks = markdown_to_blocks("```brainfuck\n+\n```");
+
+        assert_eq!(blocks[0]["code"]["language"], "plain text");
+    }
+}
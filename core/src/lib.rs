@@ -1,17 +1,17 @@
 pub use block::*;
+pub use error::RenderError;
+pub use import::markdown_to_blocks;
+pub use naming::{DEFAULT_MAX_FILE_NAME_LENGTH, FileNamer, NamingStrategy, escape_page_title};
+pub use page_registry::{PageRegistry, linked_page_ids, normalize_page_id};
+pub use snapshot::{SNAPSHOT_VERSION, Snapshot, SnapshotPage};
+pub use users::{User, UserDirectory, mentioned_user_ids};
 
 mod block;
+mod error;
+mod import;
+mod naming;
+mod page_registry;
 mod rich_text;
-
-pub(crate) fn escape_page_title(title: &str) -> String {
-    title
-        .chars()
-        .map(|c| {
-            if c.is_whitespace() || c == '\u{200B}' {
-                '_'
-            } else {
-                c
-            }
-        })
-        .collect()
-}
+mod snapshot;
+mod users;
+mod width;
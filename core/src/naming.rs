@@ -0,0 +1,226 @@
+use std::collections::HashSet;
+
+use unicode_normalization::{UnicodeNormalization, char::is_combining_mark};
+
+/// Longest file name, in bytes and without extension, a namer returns by default. Leaves
+/// room for collision suffixes and extensions within the usual 255 byte limit.
+pub const DEFAULT_MAX_FILE_NAME_LENGTH: usize = 100;
+
+/// Characters Windows, macOS or Linux don't allow in file names.
+const INVALID_FILE_NAME_CHARS: &[char] = &['/', '\\', ':', '*', '?', '"', '<', '>', '|'];
+
+/// Names Windows reserves for devices, with or without an extension.
+const RESERVED_FILE_NAMES: &[&str] = &[
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
+    "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+/// How exported pages are named on disk.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum NamingStrategy {
+    /// The title with whitespace and characters invalid in file names replaced by `_`,
+    /// e.g. `Meeting_notes`.
+    #[default]
+    Title,
+    /// Lowercase words joined by `-`, with diacritics removed, e.g. `meeting-notes`.
+    Slug,
+    /// The title followed by the first 8 characters of the page ID, e.g.
+    /// `Meeting_notes-0123abcd`.
+    TitleWithId,
+    /// The page ID without dashes.
+    Id,
+}
+
+/// Hands out file names for pages, none of which collide within the same directory.
+///
+/// Names are compared case-insensitively, as they are on Windows and macOS. A name that is
+/// taken gets `-2`, `-3` and so on appended, so the result only depends on the order pages are
+/// named in.
+#[derive(Clone, Debug)]
+pub struct FileNamer {
+    strategy: NamingStrategy,
+    max_length: usize,
+    taken: HashSet<(String, String)>,
+}
+
+impl FileNamer {
+    pub fn new(strategy: NamingStrategy) -> FileNamer {
+        FileNamer {
+            strategy,
+            max_length: DEFAULT_MAX_FILE_NAME_LENGTH,
+            taken: HashSet::new(),
+        }
+    }
+
+    /// Longest name in bytes, collision suffix included.
+    pub fn max_length(mut self, max_length: usize) -> FileNamer {
+        self.max_length = max_length;
+        self
+    }
+
+    /// Keeps `name` from being handed out in `dir`, e.g. for an index file.
+    pub fn reserve(&mut self, dir: &str, name: &str) {
+        self.taken.insert((dir.into(), name.to_lowercase()));
+    }
+
+    /// A name without extension for the page `page_id` titled `title`, unique in `dir`.
+    pub fn name(&mut self, dir: &str, title: &str, page_id: &str) -> String {
+        let id: String = page_id.chars().filter(|c| *c != '-').collect();
+        let name = match self.strategy {
+            NamingStrategy::Title => escape_page_title(title),
+            NamingStrategy::Slug => slugify(title),
+            NamingStrategy::TitleWithId => {
+                let suffix = format!("-{}", id.chars().take(8).collect::<String>());
+                let title = escape_page_title(title);
+                let title = truncate(&title, self.max_length.saturating_sub(suffix.len()));
+                format!("{}{}", title, suffix)
+            }
+            NamingStrategy::Id => id.clone(),
+        };
+        let name = match truncate(&name, self.max_length) {
+            "" => id,
+            name => name.to_string(),
+        };
+
+        let mut candidate = name.clone();
+        let mut counter = 1;
+        while !self.taken.insert((dir.into(), candidate.to_lowercase())) {
+            counter += 1;
+            let suffix = format!("-{}", counter);
+            candidate = format!(
+                "{}{}",
+                truncate(&name, self.max_length.saturating_sub(suffix.len())),
+                suffix
+            );
+        }
+        candidate
+    }
+}
+
+/// `title` as a file name: normalized to NFC, as titles typed on macOS often arrive
+/// decomposed, with whitespace and characters invalid on common file systems replaced by
+/// `_`, no leading or trailing dots, and a `_` appended to names Windows reserves.
+pub fn escape_page_title(title: &str) -> String {
+    let escaped: String = title
+        .nfc()
+        .map(|c| {
+            if c.is_whitespace()
+                || c.is_control()
+                || c == '\u{200B}'
+                || INVALID_FILE_NAME_CHARS.contains(&c)
+            {
+                '_'
+            } else {
+                c
+            }
+        })
+        .collect();
+    let escaped = escaped.trim_matches('.');
+
+    let stem = escaped.split('.').next().unwrap_or_default();
+    if RESERVED_FILE_NAMES
+        .iter()
+        .any(|reserved| reserved.eq_ignore_ascii_case(stem))
+    {
+        format!("{}_", escaped)
+    } else {
+        escaped.to_string()
+    }
+}
+
+/// Lowercase alphanumeric words of `title` joined by `-`, with compatibility characters such
+/// as ligatures and full-width letters replaced and diacritics removed.
+fn slugify(title: &str) -> String {
+    let mut slug = String::new();
+    let letters = title.nfkd().filter(|c| !is_combining_mark(*c)).nfc();
+    for c in letters {
+        if c.is_alphanumeric() {
+            slug.extend(c.to_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    slug.trim_end_matches('-').to_string()
+}
+
+/// The longest prefix of `text` of at most `max_length` bytes that ends on a character
+/// boundary.
+fn truncate(text: &str, max_length: usize) -> &str {
+    if text.len() <= max_length {
+        return text;
+    }
+    let mut end = max_length;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    &text[..end]
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use super::{FileNamer, NamingStrategy, escape_page_title};
+
+    const ID: &str = "0123abcd-0123-abcd-0123-abcd0123abcd";
+
+    #[test]
+    fn test_escape_page_title() {
+        assert_eq!(escape_page_title("Q&A: why? <draft>"), "Q&A__why___draft_");
+        assert_eq!(escape_page_title("a/b\\c|d*e\"f"), "a_b_c_d_e_f");
+        assert_eq!(escape_page_title("...notes..."), "notes");
+        assert_eq!(escape_page_title("con"), "con_");
+        assert_eq!(escape_page_title("LPT1.backup"), "LPT1.backup_");
+        assert_eq!(escape_page_title("Console"), "Console");
+        assert_eq!(escape_page_title("Cafe\u{301}"), "Café");
+    }
+
+    #[test]
+    fn test_strategies() {
+        let name = |strategy| FileNamer::new(strategy).name("", "Réunion d'équipe 2024", ID);
+
+        assert_eq!(name(NamingStrategy::Title), "Réunion_d'équipe_2024");
+        assert_eq!(name(NamingStrategy::Slug), "reunion-d-equipe-2024");
+        assert_eq!(
+            name(NamingStrategy::TitleWithId),
+            "Réunion_d'équipe_2024-0123abcd"
+        );
+        assert_eq!(name(NamingStrategy::Id), "0123abcd0123abcd0123abcd0123abcd");
+    }
+
+    #[test]
+    fn test_slug_keeps_non_latin_letters() {
+        let mut namer = FileNamer::new(NamingStrategy::Slug);
+
+        assert_eq!(namer.name("", "会議メモ (2024)", ID), "会議メモ-2024");
+        assert_eq!(namer.name("", "회의 메모", ID), "회의-메모");
+        assert_eq!(namer.name("", "Ｑ３ ﬁnance", ID), "q3-finance");
+        assert_eq!(
+            namer.name("", "???", ID),
+            "0123abcd0123abcd0123abcd0123abcd"
+        );
+    }
+
+    #[test]
+    fn test_collisions() {
+        let mut namer = FileNamer::new(NamingStrategy::Title);
+        namer.reserve("docs", "index");
+
+        assert_eq!(namer.name("", "Meeting notes", ID), "Meeting_notes");
+        assert_eq!(namer.name("", "Meeting notes", ID), "Meeting_notes-2");
+        assert_eq!(namer.name("", "meeting NOTES", ID), "meeting_NOTES-3");
+        assert_eq!(namer.name("other", "Meeting notes", ID), "Meeting_notes");
+        assert_eq!(namer.name("docs", "Index", ID), "Index-2");
+    }
+
+    #[test]
+    fn test_length_limit() {
+        let mut namer = FileNamer::new(NamingStrategy::TitleWithId).max_length(14);
+
+        assert_eq!(namer.name("", "ééééé", ID), "éé-0123abcd");
+
+        let mut namer = FileNamer::new(NamingStrategy::Title).max_length(5);
+        assert_eq!(namer.name("", "abcdefgh", ID), "abcde");
+        assert_eq!(namer.name("", "abcdefgh", ID), "abc-2");
+    }
+}
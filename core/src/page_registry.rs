@@ -0,0 +1,310 @@
+use std::collections::HashMap;
+
+use percent_encoding::{AsciiSet, CONTROLS, utf8_percent_encode};
+use serde_json::Value;
+
+use crate::{Block, block::NOTION_ORIGIN};
+
+/// Paths and titles of the pages written by an export, paths relative to its root, keyed by
+/// page ID.
+///
+/// Renderers consult it so links to exported pages point at the local file instead of Notion.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PageRegistry {
+    pages: HashMap<String, RegisteredPage>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct RegisteredPage {
+    path: String,
+    title: String,
+}
+
+/// Characters that can't appear as themselves in a link destination, or would end it or
+/// start its fragment or query.
+const PATH_SEGMENT: &AsciiSet = &CONTROLS
+    .add(b' ')
+    .add(b'"')
+    .add(b'#')
+    .add(b'%')
+    .add(b'(')
+    .add(b')')
+    .add(b'<')
+    .add(b'>')
+    .add(b'?')
+    .add(b'[')
+    .add(b'\\')
+    .add(b']')
+    .add(b'^')
+    .add(b'`')
+    .add(b'{')
+    .add(b'|')
+    .add(b'}');
+
+impl PageRegistry {
+    pub fn new() -> PageRegistry {
+        PageRegistry::default()
+    }
+
+    /// Registers `page_id`, with or without dashes, as the page `title` written to `path`.
+    pub fn insert(&mut self, page_id: &str, path: impl Into<String>, title: impl Into<String>) {
+        self.pages.insert(
+            normalize_page_id(page_id),
+            RegisteredPage {
+                path: path.into(),
+                title: title.into(),
+            },
+        );
+    }
+
+    pub fn path(&self, page_id: &str) -> Option<&str> {
+        self.pages
+            .get(&normalize_page_id(page_id))
+            .map(|page| page.path.as_str())
+    }
+
+    pub fn title(&self, page_id: &str) -> Option<&str> {
+        self.pages
+            .get(&normalize_page_id(page_id))
+            .map(|page| page.title.as_str())
+    }
+
+    pub fn len(&self) -> usize {
+        self.pages.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pages.is_empty()
+    }
+}
+
+/// IDs of the pages and databases `blocks` and their children link to, without dashes and
+/// each once: child pages and databases, `link_to_page` targets, page mentions and Notion
+/// URLs in links. A page has to be rendered again when one of them moves.
+pub fn linked_page_ids(blocks: &[Block]) -> Vec<String> {
+    let mut ids = Vec::new();
+    for block in blocks {
+        // Links sit in a different field for every block type, so they are found in the
+        // serialized block, which includes the children.
+        if let Ok(value) = serde_json::to_value(block) {
+            collect_links(&value, &mut ids);
+        }
+    }
+    ids
+}
+
+fn collect_links(value: &Value, ids: &mut Vec<String>) {
+    let mut push = |id: String| {
+        if !ids.contains(&id) {
+            ids.push(id);
+        }
+    };
+
+    match value {
+        Value::Object(object) => {
+            if matches!(
+                object.get("type").and_then(Value::as_str),
+                Some("child_page" | "child_database")
+            ) && let Some(id) = object.get("id").and_then(Value::as_str)
+            {
+                push(normalize_page_id(id));
+            }
+            if let Some(link) = object.get("link_to_page")
+                && let Some(id) = link["page_id"].as_str().or(link["database_id"].as_str())
+            {
+                push(normalize_page_id(id));
+            }
+            if let Some(mention) = object.get("mention")
+                && mention["type"] == "page"
+                && let Some(id) = mention["page"]["id"].as_str()
+            {
+                push(normalize_page_id(id));
+            }
+            if let Some(id) = object
+                .get("href")
+                .and_then(Value::as_str)
+                .and_then(page_id_from_url)
+            {
+                push(id);
+            }
+            for value in object.values() {
+                collect_links(value, ids);
+            }
+        }
+        Value::Array(values) => {
+            for value in values {
+                collect_links(value, ids);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Notion accepts page IDs with and without dashes, in either case.
+pub fn normalize_page_id(page_id: &str) -> String {
+    page_id
+        .chars()
+        .filter(|c| *c != '-')
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
+/// The page ID in a Notion URL such as `https://www.notion.so/workspace/Title-0123…cdef`,
+/// which is the last 32 hex digits of the last path segment.
+pub(crate) fn page_id_from_url(url: &str) -> Option<String> {
+    let rest = url
+        .strip_prefix(NOTION_ORIGIN)
+        .or_else(|| url.strip_prefix("https://notion.so"))?;
+    let path = rest.split(['?', '#']).next().unwrap_or_default();
+    let segment = path.trim_end_matches('/').rsplit('/').next()?;
+    let id: Vec<char> = normalize_page_id(segment).chars().collect();
+
+    let id = &id[id.len().checked_sub(32)?..];
+    id.iter()
+        .all(|c| c.is_ascii_hexdigit())
+        .then(|| id.iter().collect())
+}
+
+/// Path of `to` relative to the directory of `from`, both relative to the export root.
+pub(crate) fn relative_path(from: &str, to: &str) -> String {
+    let mut from_dirs: Vec<&str> = from.split('/').collect();
+    from_dirs.pop();
+    let to_parts: Vec<&str> = to.split('/').collect();
+
+    let common = from_dirs
+        .iter()
+        .zip(&to_parts)
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let mut parts = vec![".."; from_dirs.len() - common];
+    parts.extend(&to_parts[common..]);
+    parts.join("/")
+}
+
+/// `path` as a link destination, with each segment percent-encoded so titles containing
+/// spaces, parentheses, `#` or `%` still link to the file.
+pub(crate) fn encode_path(path: &str) -> String {
+    path.split('/')
+        .map(|segment| utf8_percent_encode(segment, PATH_SEGMENT).to_string())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+#[cfg(test)]
+mod test {
+
+    use pretty_assertions::assert_eq;
+
+    use serde_json::json;
+
+    use crate::Block;
+
+    use super::{PageRegistry, encode_path, linked_page_ids, page_id_from_url, relative_path};
+
+    #[test]
+    fn test_path_ignores_dashes_and_case() {
+        let mut registry = PageRegistry::new();
+        registry.insert("0123abcd-0123-abcd-0123-abcd0123abcd", "Page.md", "Page");
+
+        assert_eq!(
+            registry.path("0123ABCD0123ABCD0123ABCD0123ABCD"),
+            Some("Page.md")
+        );
+        assert_eq!(
+            registry.title("0123ABCD0123ABCD0123ABCD0123ABCD"),
+            Some("Page")
+        );
+        assert_eq!(registry.path("ffffffff-0123-abcd-0123-abcd0123abcd"), None);
+    }
+
+    #[test]
+    fn test_page_id_from_url() {
+        assert_eq!(
+            page_id_from_url(
+                "https://www.notion.so/workspace/Title-0123abcd0123abcd0123abcd0123abcd?pvs=4"
+            ),
+            Some("0123abcd0123abcd0123abcd0123abcd".into())
+        );
+        assert_eq!(
+            page_id_from_url("https://www.notion.so/0123abcd-0123-abcd-0123-abcd0123abcd"),
+            Some("0123abcd0123abcd0123abcd0123abcd".into())
+        );
+        assert_eq!(page_id_from_url("https://www.notion.so/pricing"), None);
+        assert_eq!(
+            page_id_from_url("https://example.com/0123abcd0123abcd0123abcd0123abcd"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_relative_path() {
+        assert_eq!(relative_path("Page.md", "Other.md"), "Other.md");
+        assert_eq!(relative_path("a/Page.md", "a/b/Other.md"), "b/Other.md");
+        assert_eq!(
+            relative_path("a/b/Page.md", "c/Other.md"),
+            "../../c/Other.md"
+        );
+    }
+
+    #[test]
+    fn test_encode_path() {
+        assert_eq!(encode_path("a/Page.md"), "a/Page.md");
+        assert_eq!(
+            encode_path("Q&A (#1)/100% done.md"),
+            "Q&A%20%28%231%29/100%25%20done.md"
+        );
+        assert_eq!(encode_path("日本.md"), "%E6%97%A5%E6%9C%AC.md");
+    }
+
+    #[test]
+    fn test_linked_page_ids() {
+        let blocks: Vec<Block> = serde_json::from_value(json!([
+            {
+                "type": "child_page",
+                "id": "aaaaaaaa-aaaa-aaaa-aaaa-aaaaaaaaaaaa",
+                "child_page": { "title": "Child" }
+            },
+            {
+                "type": "link_to_page",
+                "id": "link-id",
+                "link_to_page": { "type": "database_id", "database_id": "bbbbbbbb-bbbb-bbbb-bbbb-bbbbbbbbbbbb" }
+            },
+            {
+                "type": "toggle",
+                "id": "toggle-id",
+                "toggle": { "rich_text": [] },
+                "children": [{
+                    "type": "paragraph",
+                    "id": "paragraph-id",
+                    "paragraph": {
+                        "rich_text": [
+                            {
+                                "type": "mention",
+                                "mention": { "type": "page", "page": { "id": "cccccccc-cccc-cccc-cccc-cccccccccccc" } },
+                                "plain_text": "Mentioned"
+                            },
+                            {
+                                "type": "text",
+                                "text": { "content": "link" },
+                                "plain_text": "link",
+                                "href": "https://www.notion.so/Linked-dddddddddddddddddddddddddddddddd"
+                            }
+                        ]
+                    }
+                }]
+            }
+        ]))
+        .unwrap();
+
+        assert_eq!(
+            linked_page_ids(&blocks),
+            vec![
+                "aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa",
+                "bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb",
+                "cccccccccccccccccccccccccccccccc",
+                "dddddddddddddddddddddddddddddddd"
+            ]
+        );
+    }
+}
@@ -1,55 +1,76 @@
-use serde;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Deserialize, Clone)]
+use crate::{MarkdownRenderOptions, block::NOTION_ORIGIN};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub(crate) enum RichText {
     Text {
         plain_text: String,
         #[serde(skip_serializing_if = "Option::is_none")]
         href: Option<String>,
-        #[serde(skip_serializing_if = "Option::is_none", default)]
+        #[serde(default)]
         annotations: Annotations,
     },
     Mention {
         mention: Mention,
+        #[serde(default)]
+        plain_text: String,
         #[serde(skip_serializing_if = "Option::is_none")]
         href: Option<String>,
-        #[serde(skip_serializing_if = "Option::is_none", default)]
+        #[serde(default)]
         annotations: Annotations,
     },
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub(crate) enum Mention {
     #[serde(rename = "link_mention")]
     Link(LinkMention),
     User(UserMention),
+    Page(PageMention),
+    /// Dates, databases and other mentions, rendered as their plain text.
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub(crate) struct PageMention {
+    page: PageMentionContent,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub(crate) struct PageMentionContent {
+    id: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub(crate) struct UserMention {
     user: UserMentionContent,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub(crate) struct UserMentionContent {
-    name: String,
+    #[serde(default)]
+    id: String,
+    /// Missing for bots and guests whose names aren't shared with the integration.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub(crate) struct LinkMention {
     #[serde(rename = "link_mention")]
     link: LinkMentionContent,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub(crate) struct LinkMentionContent {
     title: String,
 }
 
-#[derive(Debug, Deserialize, PartialEq, Clone)]
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
 #[serde(rename_all = "snake_case")]
 pub struct Annotations {
     bold: bool,
@@ -59,18 +80,6 @@ pub struct Annotations {
     underline: bool,
 }
 
-impl Default for Annotations {
-    fn default() -> Annotations {
-        Annotations {
-            bold: false,
-            italic: false,
-            strikethrough: false,
-            code: false,
-            underline: false,
-        }
-    }
-}
-
 impl RichText {
     fn bold(text: &str) -> String {
         format!("**{}**", text)
@@ -84,8 +93,17 @@ impl RichText {
         format!("~~{}~~", text)
     }
 
+    /// Wraps `text` in a code span that keeps it verbatim: the fence is longer than any
+    /// backtick run inside, and padding is added where CommonMark would strip or merge it.
     fn code(text: &str) -> String {
-        format!("`{}`", text)
+        let longest_run = text.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+        let fence = "`".repeat(longest_run + 1);
+        let padded = text.starts_with('`')
+            || text.ends_with('`')
+            || (text.starts_with(' ') && text.ends_with(' ') && !text.trim().is_empty());
+        let padding = if padded { " " } else { "" };
+
+        format!("{}{}{}{}{}", fence, padding, text, padding, fence)
     }
 
     fn link(text: &str, url: &str) -> String {
@@ -93,7 +111,18 @@ impl RichText {
     }
 
     fn text_to_markdown(
-        plain_text: &String,
+        plain_text: &str,
+        href: &Option<String>,
+        annotations: &Annotations,
+    ) -> String {
+        Self::annotate(plain_text, escape_markdown, href, annotations)
+    }
+
+    /// Formats `text` with its annotations and link, after `to_markdown` turned it into
+    /// Markdown. Code spans hold `text` as it is.
+    fn annotate(
+        plain_text: &str,
+        to_markdown: impl Fn(&str) -> String,
         href: &Option<String>,
         annotations: &Annotations,
     ) -> String {
@@ -102,24 +131,27 @@ impl RichText {
             .take_while(|c| c.is_whitespace())
             .collect::<String>();
         let trimmed_plain_text = plain_text.trim().to_string();
-        let trailing_space =
-            if trimmed_plain_text.is_empty() && plain_text.chars().all(|c| c.is_whitespace()) {
-                String::new()
-            } else {
-                plain_text
-                    .chars()
-                    .rev()
-                    .take_while(|c| c.is_whitespace())
-                    .collect::<String>()
-                    .chars()
-                    .rev()
-                    .collect::<String>()
+        let trailing_space = plain_text
+            .chars()
+            .rev()
+            .take_while(|c| c.is_whitespace())
+            .collect::<String>()
+            .chars()
+            .rev()
+            .collect::<String>();
+
+        // Emphasis around nothing would render as literal `****`.
+        if trimmed_plain_text.is_empty() && !annotations.code {
+            return match href {
+                Some(url) => Self::link(plain_text, url),
+                None => plain_text.to_string(),
             };
+        }
 
         let mut markdown_text = if annotations.code {
-            Self::code(&plain_text)
+            Self::code(plain_text)
         } else {
-            trimmed_plain_text
+            to_markdown(&trimmed_plain_text)
         };
 
         if annotations.bold {
@@ -145,45 +177,162 @@ impl RichText {
         markdown_text
     }
 
-    fn link_to_markdown(
-        title: &String,
-        href: &Option<String>,
-        annotations: &Annotations,
-    ) -> String {
+    fn link_to_markdown(title: &str, href: &Option<String>, annotations: &Annotations) -> String {
         Self::text_to_markdown(title, href, annotations)
     }
 
-    fn user_to_markdown(name: &String, href: &Option<String>, annotations: &Annotations) -> String {
-        Self::text_to_markdown(name, href, annotations)
+    /// `mention` is Markdown already, the user mention template with the user's fields.
+    fn user_to_markdown(mention: &str, href: &Option<String>, annotations: &Annotations) -> String {
+        Self::annotate(mention, str::to_string, href, annotations)
+    }
+
+    fn is_code(&self) -> bool {
+        match self {
+            RichText::Text { annotations, .. } | RichText::Mention { annotations, .. } => {
+                annotations.code
+            }
+        }
+    }
+
+    pub(crate) fn plain_text(&self) -> &str {
+        match self {
+            RichText::Text { plain_text, .. } | RichText::Mention { plain_text, .. } => plain_text,
+        }
     }
 
-    pub(crate) fn to_markdown(&self) -> String {
+    pub(crate) fn to_markdown(&self, options: &MarkdownRenderOptions) -> String {
         match self {
             RichText::Text {
                 plain_text,
                 href,
                 annotations,
-            } => Self::text_to_markdown(plain_text, href, annotations),
+            } => {
+                let href = href.as_deref().map(|href| options.resolve_href(href));
+                Self::text_to_markdown(plain_text, &href, annotations)
+            }
             RichText::Mention {
                 mention,
+                plain_text,
                 href,
                 annotations,
-            } => match mention {
-                Mention::Link(item) => Self::link_to_markdown(&item.link.title, href, annotations),
-                Mention::User(item) => Self::user_to_markdown(&item.user.name, href, annotations),
-            },
+            } => {
+                let href = href.as_deref().map(|href| options.resolve_href(href));
+                match mention {
+                    Mention::Link(item) => {
+                        Self::link_to_markdown(&item.link.title, &href, annotations)
+                    }
+                    Mention::User(item) => {
+                        let text = options.user_mention(&item.user.id, item.user.name.as_deref());
+                        Self::user_to_markdown(&text, &href, annotations)
+                    }
+                    Mention::Page(item) => {
+                        let href = options
+                            .local_page_path(&item.page.id)
+                            .or(href)
+                            .or_else(|| Some(format!("{}/{}", NOTION_ORIGIN, item.page.id)));
+                        Self::text_to_markdown(plain_text, &href, annotations)
+                    }
+                    Mention::Other => Self::text_to_markdown(plain_text, &href, annotations),
+                }
+            }
         }
     }
 }
 
 pub trait RichTextVec {
-    fn to_markdown(&self) -> String;
+    /// Renders text that starts a line, such as a paragraph's.
+    fn to_markdown_with_options(&self, options: &MarkdownRenderOptions) -> String;
+
+    /// Renders text that follows other Markdown on its line, such as a heading's or a table
+    /// cell's, where nothing it starts with could begin another block.
+    fn to_inline_markdown_with_options(&self, options: &MarkdownRenderOptions) -> String;
+
+    /// Renders text as the content of a GFM table cell, which ends at any unescaped `|`, code
+    /// spans included, and at the end of the line.
+    fn to_table_cell_markdown_with_options(&self, options: &MarkdownRenderOptions) -> String;
 }
 
 impl RichTextVec for Vec<RichText> {
-    fn to_markdown(&self) -> String {
+    fn to_inline_markdown_with_options(&self, options: &MarkdownRenderOptions) -> String {
         self.iter()
-            .map(|rich_text| rich_text.to_markdown())
+            .map(|rich_text| rich_text.to_markdown(options))
             .collect()
     }
+
+    fn to_table_cell_markdown_with_options(&self, options: &MarkdownRenderOptions) -> String {
+        self.iter()
+            .map(|rich_text| {
+                let markdown = rich_text.to_markdown(options);
+                // Text outside code spans has its `|` escaped already. Code spans can't hold
+                // a line break, so newlines there become spaces as CommonMark would show them.
+                if rich_text.is_code() {
+                    markdown.replace('|', "\\|").replace('\n', " ")
+                } else {
+                    markdown.replace('\n', "<br>")
+                }
+            })
+            .collect()
+    }
+
+    fn to_markdown_with_options(&self, options: &MarkdownRenderOptions) -> String {
+        let mut markdown = String::new();
+        for rich_text in self {
+            let text = rich_text.to_markdown(options);
+            if markdown.is_empty() || markdown.ends_with('\n') {
+                markdown.push_str(&escape_line_start(&text));
+            } else {
+                markdown.push_str(&text);
+            }
+        }
+        markdown
+    }
+}
+
+/// Escapes the characters of `text` that Markdown would read as formatting, links, HTML or
+/// math, and what would start another block at the beginning of a line inside it.
+pub(crate) fn escape_markdown(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    let mut word_start = true;
+    for (index, c) in text.char_indices() {
+        let special = match c {
+            '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '~' | '$' | '|' => true,
+            // Starts a heading, or closes one when it ends the line.
+            '#' => word_start,
+            '&' => is_entity(&text[index + 1..]),
+            _ => false,
+        };
+        if special {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+        word_start = c.is_whitespace();
+    }
+
+    let mut lines = escaped.split('\n');
+    let first = lines.next().unwrap_or_default().to_string();
+    lines.fold(first, |text, line| text + "\n" + &escape_line_start(line))
+}
+
+/// Whether an `&` followed by `rest` would be read as an entity, such as `&amp;`.
+fn is_entity(rest: &str) -> bool {
+    let name_length = rest
+        .find(|c: char| !c.is_ascii_alphanumeric() && c != '#')
+        .unwrap_or(rest.len());
+    name_length > 0 && rest[name_length..].starts_with(';')
+}
+
+/// Escapes the marker that would make `line`, Markdown at the start of a line, a heading,
+/// list item, quote, thematic break or setext underline.
+fn escape_line_start(line: &str) -> String {
+    let text = line.trim_start_matches([' ', '\t']);
+    let indent = &line[..line.len() - text.len()];
+    let digits = text.len() - text.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+
+    match text[digits..].chars().next() {
+        Some('.' | ')') if digits > 0 => {
+            format!("{}{}\\{}", indent, &text[..digits], &text[digits..])
+        }
+        Some('#' | '>' | '-' | '+' | '=') if digits == 0 => format!("{}\\{}", indent, text),
+        _ => line.to_string(),
+    }
 }
@@ -0,0 +1,120 @@
+//! Offline page snapshots: a page and its fully hydrated block tree as JSON.
+//!
+//! Blocks are stored as the API returns them, with their envelope fields such as `id` and
+//! `has_children`, and their children nested under `children`.
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Block, BlockChildren, MarkdownRenderOptions};
+
+/// Format version written into every snapshot, bumped on incompatible changes.
+pub const SNAPSHOT_VERSION: u32 = 2;
+
+/// A fully hydrated page, stored so it can be rendered again without calling the API.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Snapshot {
+    pub version: u32,
+    pub page: SnapshotPage,
+    /// Top-level blocks, each with its children and envelope.
+    pub blocks: Vec<Block>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SnapshotPage {
+    pub id: String,
+    pub title: String,
+    pub last_edited_time: String,
+}
+
+impl Snapshot {
+    pub fn new(page: SnapshotPage, blocks: Vec<Block>) -> Snapshot {
+        Snapshot {
+            version: SNAPSHOT_VERSION,
+            page,
+            blocks,
+        }
+    }
+
+    pub fn to_markdown_with_options(&self, options: &MarkdownRenderOptions) -> String {
+        self.blocks.to_markdown_with_options(0, options)
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    use crate::block::{Block, MarkdownRenderOptions};
+
+    use super::{SNAPSHOT_VERSION, Snapshot, SnapshotPage};
+
+    fn snapshot() -> Snapshot {
+        let mut toggle: Block =
+            serde_json::from_str(include_str!("tests/block/toggle_response.json")).unwrap();
+        toggle.append(
+            serde_json::from_str(include_str!("tests/block/paragraph_response.json")).unwrap(),
+        );
+
+        Snapshot::new(
+            SnapshotPage {
+                id: "page-id".into(),
+                title: "this is page".into(),
+                last_edited_time: "2000-01-01T00:00:00.000Z".into(),
+            },
+            vec![toggle],
+        )
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let snapshot = snapshot();
+
+        let json = serde_json::to_string(&snapshot).unwrap();
+        let parsed: Snapshot = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(parsed.page, snapshot.page);
+        assert_eq!(parsed.blocks[0].envelope(), snapshot.blocks[0].envelope());
+        assert_eq!(parsed.blocks[0].children().len(), 1);
+        assert_eq!(
+            parsed.to_markdown_with_options(&MarkdownRenderOptions::default()),
+            snapshot.to_markdown_with_options(&MarkdownRenderOptions::default())
+        );
+    }
+
+    #[test]
+    fn test_json_format() {
+        let json = serde_json::to_value(snapshot()).unwrap();
+        let toggle = &json["blocks"][0];
+
+        assert_eq!(SNAPSHOT_VERSION, 2);
+        assert_eq!(json["version"], json!(SNAPSHOT_VERSION));
+        assert_eq!(
+            json["page"],
+            json!({
+                "id": "page-id",
+                "title": "this is page",
+                "last_edited_time": "2000-01-01T00:00:00.000Z"
+            })
+        );
+        assert_eq!(toggle["type"], "toggle");
+        assert_eq!(toggle["id"], "XXXXXXXX-XXXX-XXXX-XXXX-XXXXXXXXXXXX");
+        assert_eq!(toggle["has_children"], true);
+        assert_eq!(toggle["children"][0]["type"], "paragraph");
+        assert!(toggle.get("block").is_none());
+    }
+
+    #[test]
+    fn test_to_markdown() {
+        assert_eq!(
+            snapshot().to_markdown_with_options(&MarkdownRenderOptions::default()),
+            indoc! {r#"
+                this is toggle
+
+                this is paragraph
+            "#}
+        )
+    }
+}
@@ -0,0 +1,180 @@
+use std::collections::HashMap;
+
+use serde_json::Value;
+
+use crate::{Block, page_registry::normalize_page_id};
+
+/// A Notion user, as the users API describes them.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct User {
+    pub id: String,
+    /// Missing for bots and guests whose names aren't shared with the integration.
+    pub name: Option<String>,
+    /// Only set for people, when the integration may read email addresses.
+    pub email: Option<String>,
+}
+
+/// Users that mentions resolve against, keyed by user ID, and the handles they go by
+/// elsewhere, such as on GitHub.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct UserDirectory {
+    users: HashMap<String, User>,
+    handles: HashMap<String, String>,
+}
+
+impl UserDirectory {
+    pub fn new() -> UserDirectory {
+        UserDirectory::default()
+    }
+
+    pub fn insert(&mut self, user: User) {
+        self.users.insert(user_key(&user.id), user);
+    }
+
+    /// Sets the handle of the user with the ID or email address `user`.
+    pub fn insert_handle(&mut self, user: &str, handle: impl Into<String>) {
+        self.handles.insert(user_key(user), handle.into());
+    }
+
+    pub fn get(&self, user_id: &str) -> Option<&User> {
+        self.users.get(&user_key(user_id))
+    }
+
+    /// The handle of the user with ID `user_id`, set for the ID or else for `email`.
+    pub(crate) fn handle(&self, user_id: &str, email: Option<&str>) -> Option<&str> {
+        self.handles
+            .get(&user_key(user_id))
+            .or_else(|| self.handles.get(&user_key(email?)))
+            .map(String::as_str)
+    }
+
+    pub fn len(&self) -> usize {
+        self.users.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.users.is_empty()
+    }
+}
+
+/// IDs of the users mentioned in `blocks` and their children, each once, in the order they
+/// are first mentioned.
+pub fn mentioned_user_ids(blocks: &[Block]) -> Vec<String> {
+    let mut ids = Vec::new();
+    for block in blocks {
+        // Every block type keeps its rich text in a field of its own, so mentions are found
+        // in the serialized block, which includes the children.
+        if let Ok(value) = serde_json::to_value(block) {
+            collect_user_mentions(&value, &mut ids);
+        }
+    }
+    ids
+}
+
+fn collect_user_mentions(value: &Value, ids: &mut Vec<String>) {
+    match value {
+        Value::Object(object) => {
+            if let Some(mention) = object.get("mention")
+                && mention["type"] == "user"
+                && let Some(id) = mention["user"]["id"].as_str()
+                && !ids.iter().any(|known| user_key(known) == user_key(id))
+            {
+                ids.push(id.to_string());
+            }
+            for value in object.values() {
+                collect_user_mentions(value, ids);
+            }
+        }
+        Value::Array(values) => {
+            for value in values {
+                collect_user_mentions(value, ids);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// User IDs are matched like page IDs, email addresses regardless of case.
+fn user_key(user: &str) -> String {
+    if user.contains('@') {
+        user.to_lowercase()
+    } else {
+        normalize_page_id(user)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use serde_json::json;
+
+    use crate::Block;
+
+    use super::{User, UserDirectory, mentioned_user_ids};
+
+    #[test]
+    fn test_lookup_ignores_dashes_and_case() {
+        let mut users = UserDirectory::new();
+        users.insert(User {
+            id: "0123abcd-0123-abcd-0123-abcd0123abcd".into(),
+            name: Some("Ada".into()),
+            email: Some("ada@example.com".into()),
+        });
+        users.insert_handle("0123ABCD0123ABCD0123ABCD0123ABCD", "ada");
+        users.insert_handle("Grace@Example.com", "grace");
+
+        assert_eq!(
+            users
+                .get("0123ABCD0123ABCD0123ABCD0123ABCD")
+                .and_then(|user| user.name.as_deref()),
+            Some("Ada")
+        );
+        assert_eq!(
+            users.handle("0123abcd-0123-abcd-0123-abcd0123abcd", None),
+            Some("ada")
+        );
+        assert_eq!(
+            users.handle("ffffffff", Some("grace@example.com")),
+            Some("grace")
+        );
+        assert_eq!(users.handle("ffffffff", None), None);
+    }
+
+    #[test]
+    fn test_mentioned_user_ids() {
+        let mention = |id: &str| {
+            json!({
+                "type": "mention",
+                "mention": { "type": "user", "user": { "object": "user", "id": id } },
+                "plain_text": "@someone"
+            })
+        };
+        let blocks: Vec<Block> = serde_json::from_value(json!([
+            {
+                "type": "paragraph",
+                "id": "paragraph-id",
+                "paragraph": { "rich_text": [mention("0123abcd-0123-abcd-0123-abcd0123abcd")] }
+            },
+            {
+                "type": "toggle",
+                "id": "toggle-id",
+                "toggle": { "rich_text": [mention("ffffffff-ffff-ffff-ffff-ffffffffffff")] },
+                "children": [{
+                    "type": "paragraph",
+                    "id": "child-id",
+                    "paragraph": { "rich_text": [mention("0123abcd0123abcd0123abcd0123abcd")] }
+                }]
+            }
+        ]))
+        .unwrap();
+
+        assert_eq!(
+            mentioned_user_ids(&blocks),
+            vec![
+                "0123abcd-0123-abcd-0123-abcd0123abcd",
+                "ffffffff-ffff-ffff-ffff-ffffffffffff"
+            ]
+        );
+    }
+}
@@ -0,0 +1,43 @@
+//! Display width of text in a monospace font, for aligning table columns.
+//!
+//! Text is split into grapheme clusters, so a letter with combining marks, a flag or an emoji
+//! joined with `ZWJ` counts as one character. A cluster takes the width the Unicode East Asian
+//! Width and emoji presentation rules give it, at most the two columns a terminal draws.
+
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+/// Columns `text` takes in a monospace font.
+pub(crate) fn display_width(text: &str) -> usize {
+    text.graphemes(true)
+        .map(|cluster| cluster.width().min(2))
+        .sum()
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    use super::display_width;
+
+    #[rstest]
+    #[case("table row", 9)]
+    #[case("café", 4)]
+    #[case("cafe\u{301}", 4)]
+    #[case("Ångström", 8)]
+    #[case("日本語", 6)]
+    #[case("ｈｅｌｌｏ", 10)]
+    #[case("한국어", 6)]
+    #[case("\u{1100}\u{1161}\u{11A8}", 2)]
+    #[case("👍", 2)]
+    #[case("👍🏽", 2)]
+    #[case("👩‍👩‍👧‍👦", 2)]
+    #[case("🇯🇵", 2)]
+    #[case("❤️", 2)]
+    #[case("a\u{200B}b", 2)]
+    #[case("\u{FEFF}x", 1)]
+    fn test_display_width(#[case] text: &str, #[case] width: usize) {
+        assert_eq!(display_width(text), width);
+    }
+}
@@ -0,0 +1,307 @@
+//! Rendering generated block trees that the API could return, including shapes the renderer
+//! can only approximate, such as table rows outside tables, ragged tables and arbitrary text.
+//!
+//! The renderer must never panic, streaming must produce the same Markdown as rendering into
+//! a string, and the `try_` renderers must agree with the infallible ones when they succeed.
+
+use notion_to_markdown_core::{
+    Block, BlockChildren, CalloutStyle, ColumnLayout, MarkdownRenderOptions, MathDelimiters,
+    MermaidTarget,
+};
+use pretty_assertions::assert_eq;
+use proptest::prelude::*;
+use serde_json::{Value, json};
+
+const BLOCK_TYPES: &[&str] = &[
+    "paragraph",
+    "heading_1",
+    "heading_2",
+    "heading_3",
+    "bulleted_list_item",
+    "numbered_list_item",
+    "to_do",
+    "toggle",
+    "quote",
+    "callout",
+    "code",
+    "equation",
+    "divider",
+    "table",
+    "table_row",
+    "column_list",
+    "column",
+    "synced_block",
+    "child_page",
+    "child_database",
+    "link_to_page",
+    "bookmark",
+    "embed",
+    "image",
+    "breadcrumb",
+    "table_of_contents",
+    "unsupported",
+    "form",
+];
+
+#[derive(Debug, Clone)]
+struct TestBlock {
+    block_type: &'static str,
+    text: String,
+    flag: bool,
+    cells: usize,
+    children: Vec<TestBlock>,
+}
+
+fn text() -> impl Strategy<Value = String> {
+    prop_oneof![
+        "\\PC{0,12}",
+        "[`*_|#>\\[\\]()\\\\$ \n-]{0,12}",
+        "[a-z ]{0,8}",
+    ]
+}
+
+fn leaf() -> impl Strategy<Value = TestBlock> {
+    (
+        prop::sample::select(BLOCK_TYPES),
+        text(),
+        any::<bool>(),
+        0..4usize,
+    )
+        .prop_map(|(block_type, text, flag, cells)| TestBlock {
+            block_type,
+            text,
+            flag,
+            cells,
+            children: Vec::new(),
+        })
+}
+
+fn block_tree() -> impl Strategy<Value = TestBlock> {
+    leaf().prop_recursive(4, 48, 5, |inner| {
+        (leaf(), prop::collection::vec(inner, 0..5)).prop_map(|(mut block, children)| {
+            block.children = children;
+            block
+        })
+    })
+}
+
+fn rich_text(text: &str, bold: bool) -> Value {
+    json!([{
+        "type": "text",
+        "text": { "content": text, "link": null },
+        "annotations": {
+            "bold": bold,
+            "italic": false,
+            "strikethrough": false,
+            "underline": false,
+            "code": !bold,
+            "color": "default"
+        },
+        "plain_text": text,
+        "href": null
+    }])
+}
+
+fn content(block: &TestBlock, id: &str) -> Value {
+    let rich_text = rich_text(&block.text, block.flag);
+    let url = format!("https://example.com/{}", block.text);
+    match block.block_type {
+        "code" => json!({
+            "rich_text": rich_text,
+            "caption": [],
+            "language": if block.flag { "mermaid" } else { "rust" }
+        }),
+        "equation" => json!({ "expression": block.text }),
+        "table" => json!({
+            "table_width": block.cells,
+            "has_column_header": block.flag,
+            "has_row_header": false
+        }),
+        "table_row" => json!({ "cells": vec![rich_text; block.cells] }),
+        "column" => json!({ "width_ratio": if block.flag { Some(0.5) } else { None } }),
+        "synced_block" => json!({
+            "synced_from": if block.flag {
+                json!({ "type": "block_id", "block_id": "original" })
+            } else {
+                Value::Null
+            }
+        }),
+        "child_page" | "child_database" => json!({ "title": block.text }),
+        "link_to_page" => json!({ "type": "page_id", "page_id": id }),
+        "bookmark" | "embed" => json!({ "caption": [], "url": url }),
+        "image" => json!({ "caption": [], "type": "file", "file": { "url": url } }),
+        "callout" => json!({
+            "rich_text": rich_text,
+            "icon": { "type": "emoji", "emoji": "💡" },
+            "color": "default"
+        }),
+        "divider" | "breadcrumb" | "table_of_contents" | "column_list" | "unsupported" => {
+            json!({})
+        }
+        _ => json!({
+            "rich_text": rich_text,
+            "checked": block.flag,
+            "color": "default"
+        }),
+    }
+}
+
+fn to_json(block: &TestBlock, id: &mut usize) -> Value {
+    *id += 1;
+    let block_id = format!("00000000-0000-0000-0000-{:012}", id);
+    let children: Vec<Value> = block
+        .children
+        .iter()
+        .map(|child| to_json(child, id))
+        .collect();
+
+    json!({
+        "object": "block",
+        "id": block_id,
+        "has_children": !children.is_empty(),
+        "type": block.block_type,
+        block.block_type: content(block, &block_id),
+        "children": children,
+    })
+}
+
+fn options() -> impl Strategy<Value = MarkdownRenderOptions> {
+    (
+        prop::sample::select(vec![
+            ColumnLayout::Sequential,
+            ColumnLayout::HtmlFlex,
+            ColumnLayout::Table,
+        ]),
+        prop::sample::select(vec![
+            CalloutStyle::Blockquote,
+            CalloutStyle::GitHubAlert,
+            CalloutStyle::MkDocs,
+            CalloutStyle::Docusaurus,
+        ]),
+        prop::sample::select(vec![
+            MermaidTarget::GitHub,
+            MermaidTarget::MkDocs,
+            MermaidTarget::Docusaurus,
+        ]),
+        prop::sample::select(vec![
+            MathDelimiters::Dollars,
+            MathDelimiters::Brackets,
+            MathDelimiters::EscapedBrackets,
+        ]),
+        any::<bool>(),
+        any::<bool>(),
+        any::<bool>(),
+        any::<bool>(),
+    )
+        .prop_map(
+            |(
+                column_layout,
+                callout_style,
+                mermaid_target,
+                math_delimiters,
+                synced_block_markers,
+                block_anchors,
+                compact_tables,
+                infer_table_alignment,
+            )| MarkdownRenderOptions {
+                column_layout,
+                callout_style,
+                mermaid_target,
+                math_delimiters,
+                synced_block_markers,
+                block_anchors,
+                compact_tables,
+                infer_table_alignment,
+                ..Default::default()
+            },
+        )
+}
+
+fn blocks(tree: &[TestBlock]) -> Vec<Block> {
+    let mut id = 0;
+    tree.iter()
+        .map(|block| serde_json::from_value(to_json(block, &mut id)).unwrap())
+        .collect()
+}
+
+#[test]
+fn test_generated_block_types_parse() {
+    for block_type in BLOCK_TYPES {
+        let block = TestBlock {
+            block_type,
+            text: "text".into(),
+            flag: true,
+            cells: 2,
+            children: Vec::new(),
+        };
+        let parsed = &blocks(&[block])[0];
+
+        assert!(
+            !matches!(parsed, Block::Unparsed(_)),
+            "{} does not parse",
+            block_type
+        );
+    }
+}
+
+proptest! {
+    #[test]
+    fn test_rendering_never_panics(
+        tree in prop::collection::vec(block_tree(), 0..6),
+        depth in 0..3usize,
+        options in options(),
+    ) {
+        let blocks = blocks(&tree);
+
+        let markdown = blocks.to_markdown_with_options(depth, &options);
+        let mut streamed = Vec::new();
+        blocks.write_markdown_to(&mut streamed, depth, &options).unwrap();
+        assert_eq!(String::from_utf8(streamed).unwrap(), markdown);
+
+        if let Ok(checked) = blocks.try_to_markdown_with_options(depth, &options) {
+            assert_eq!(checked, markdown);
+        }
+        for block in &blocks {
+            let _ = block.to_markdown_with_options(&options);
+            let _ = block.try_to_markdown_with_options(&options);
+        }
+    }
+}
+
+#[test]
+fn test_try_render_rejects_misplaced_table_rows() {
+    let row = TestBlock {
+        block_type: "table_row",
+        text: "cell".into(),
+        flag: false,
+        cells: 2,
+        children: Vec::new(),
+    };
+    let toggle = TestBlock {
+        block_type: "toggle",
+        children: vec![row.clone()],
+        ..row.clone()
+    };
+    let table = TestBlock {
+        block_type: "table",
+        children: vec![row.clone(), toggle.clone()],
+        ..row.clone()
+    };
+    let options = MarkdownRenderOptions::default();
+
+    assert_eq!(
+        blocks(&[toggle])
+            .try_to_markdown_with_options(0, &options)
+            .unwrap_err()
+            .to_string(),
+        "table row 00000000-0000-0000-0000-000000000002 is not inside a table"
+    );
+    assert_eq!(
+        blocks(&[table])
+            .try_to_markdown_with_options(0, &options)
+            .unwrap_err()
+            .to_string(),
+        "block 00000000-0000-0000-0000-000000000003 inside table \
+         00000000-0000-0000-0000-000000000001 is not a table row"
+    );
+}